@@ -3,17 +3,19 @@ pub type ScanOutcome = Result<Option<String>, String>;
 mod scanners;
 
 use scanners::{
-    audit_disabled, bpf_kprobe_attachments, bpf_lsm, core_pattern_pipeline, cron_ghost,
-    deleted_memfd, fanotify_watchers, ftrace_redirection, hidden_bind_mounts, hidden_listeners,
-    hidden_lkm, hidden_pids, host_net_ns, host_pid_ns, journal_gaps, kernel_cmdline,
-    kernel_message_suppression, kernel_taint, kernel_text_ro, kernel_thread_masquerade, large_rx,
-    ld_audit, ld_so_preload, library_search_hijack, live_ld_preload, local_port_backdoors,
-    modprobe_hotplug, module_list_linkage_tamper, netfilter_cloaking, netfilter_hook_drift,
-    netlink_vs_proc, overlay_lowerdir, overlayfs_whiteouts, ownerless_bpf_objects,
-    ownerless_sockets, pam_nss, pins_non_bpffs, scripts_d, seccomp_user_notify,
-    sensitive_host_mounts, sensitive_kfunc, sockmap_sockhash, ssh_footholds, sudoers,
-    suspicious_ptrace, syscall_table, systemd_ghost, task_list_mismatch, unknown_kprobes,
-    xdp_tc_detached,
+    account_anomalies, audit_disabled, binfmt_misc, bpf_kprobe_attachments, bpf_lsm,
+    core_pattern_pipeline, cron_ghost, deleted_memfd, fanotify_watchers, ftrace_redirection,
+    got_hooks, hardening_posture, hidden_bind_mounts, hidden_listeners, hidden_lkm, hidden_pids,
+    host_net_ns, host_pid_ns, initramfs_contents, journal_gaps, kernel_cmdline,
+    kernel_message_suppression, kernel_taint, kernel_text_ro, kernel_thread_masquerade,
+    kmsg_forensics, large_rx, ld_audit, ld_so_preload, library_search_hijack, live_ld_preload,
+    local_port_backdoors, modprobe_hotplug, module_list_linkage_tamper, netfilter_cloaking,
+    netfilter_hook_drift, netlink_vs_proc, outbound_connections, overlay_lowerdir,
+    overlayfs_whiteouts, ownerless_bpf_objects, ownerless_sockets, packet_sniffers, pam_nss,
+    pins_non_bpffs, privileged_files, process_environ, process_masquerade, process_tree, scripts_d,
+    seccomp_user_notify, sensitive_host_mounts, sensitive_kfunc, shell_startup, sockmap_sockhash,
+    ssh_footholds, sudoers, suspicious_ptrace, syscall_table, systemd_ghost, task_list_mismatch,
+    tracefs_inventory, udev_rules, unix_sockets, unknown_kprobes, xdp_tc_detached,
 };
 
 const COLOR_GREEN: &str = "\x1b[32m";
@@ -37,13 +39,21 @@ const SCANNERS: &[Scanner] = &[
         func: hidden_lkm::run,
     },
     Scanner {
-        name: "Kernel taint with no visible cause",
+        name: "Kernel taint attribution",
         func: kernel_taint::run,
     },
+    Scanner {
+        name: "Kernel ring buffer (/dev/kmsg) forensics",
+        func: kmsg_forensics::run,
+    },
     Scanner {
         name: "Ftrace redirection on critical paths",
         func: ftrace_redirection::run,
     },
+    Scanner {
+        name: "Tracefs inventory (ftrace ops, uprobes, livepatch)",
+        func: tracefs_inventory::run,
+    },
     Scanner {
         name: "Unknown kprobes on sensitive symbols",
         func: unknown_kprobes::run,
@@ -53,9 +63,17 @@ const SCANNERS: &[Scanner] = &[
         func: syscall_table::run,
     },
     Scanner {
-        name: "modprobe helper tamper",
+        name: "Usermode helper tamper (modprobe/uevent/poweroff/request-key)",
         func: modprobe_hotplug::run,
     },
+    Scanner {
+        name: "binfmt_misc handler hijack",
+        func: binfmt_misc::run,
+    },
+    Scanner {
+        name: "udev rules persistence",
+        func: udev_rules::run,
+    },
     Scanner {
         name: "Netfilter hook drift (orphans/invalid jumps)",
         func: netfilter_hook_drift::run,
@@ -108,16 +126,24 @@ const SCANNERS: &[Scanner] = &[
         name: "Kernel thread masquerade",
         func: kernel_thread_masquerade::run,
     },
+    Scanner {
+        name: "Process masquerading (comm/argv/exe)",
+        func: process_masquerade::run,
+    },
     Scanner {
         name: "Suspicious ptrace edges",
         func: suspicious_ptrace::run,
     },
+    Scanner {
+        name: "Reverse shells and suspicious process trees",
+        func: process_tree::run,
+    },
     Scanner {
         name: "Seccomp user-notify responders",
         func: seccomp_user_notify::run,
     },
     Scanner {
-        name: "Deleted-binary or memfd processes",
+        name: "Fileless execution (deleted/memfd/tmpfs/fd exec)",
         func: deleted_memfd::run,
     },
     Scanner {
@@ -132,6 +158,10 @@ const SCANNERS: &[Scanner] = &[
         name: "Ownerless sockets",
         func: ownerless_sockets::run,
     },
+    Scanner {
+        name: "Unix domain and abstract sockets",
+        func: unix_sockets::run,
+    },
     Scanner {
         name: "Netfilter cloaking artifacts",
         func: netfilter_cloaking::run,
@@ -140,6 +170,14 @@ const SCANNERS: &[Scanner] = &[
         name: "Local port backdoors (tmp/deleted)",
         func: local_port_backdoors::run,
     },
+    Scanner {
+        name: "Outbound connections by process",
+        func: outbound_connections::run,
+    },
+    Scanner {
+        name: "Raw/packet socket sniffers (cBPF filters)",
+        func: packet_sniffers::run,
+    },
     Scanner {
         name: "ld.so.preload tamper",
         func: ld_so_preload::run,
@@ -153,9 +191,17 @@ const SCANNERS: &[Scanner] = &[
         func: systemd_ghost::run,
     },
     Scanner {
-        name: "SSH footholds (forced/wildcard/insecure)",
+        name: "SSH footholds (authorized_keys/sshd_config/rc)",
         func: ssh_footholds::run,
     },
+    Scanner {
+        name: "Shell startup/login hook persistence",
+        func: shell_startup::run,
+    },
+    Scanner {
+        name: "Account database anomalies (passwd/shadow/group)",
+        func: account_anomalies::run,
+    },
     Scanner {
         name: "OverlayFS whiteouts / opaque",
         func: overlayfs_whiteouts::run,
@@ -180,14 +226,26 @@ const SCANNERS: &[Scanner] = &[
         name: "Library search hijack (SUID/priv)",
         func: library_search_hijack::run,
     },
+    Scanner {
+        name: "SUID/SGID and file capability sweep",
+        func: privileged_files::run,
+    },
     Scanner {
         name: "LD_AUDIT in daemons (no TTY)",
         func: ld_audit::run,
     },
+    Scanner {
+        name: "Process environment/argv anomalies",
+        func: process_environ::run,
+    },
     Scanner {
         name: "Large RX-anonymous regions in daemons (non-JIT)",
         func: large_rx::run,
     },
+    Scanner {
+        name: "GOT/PLT and inline hooks in daemons",
+        func: got_hooks::run,
+    },
     Scanner {
         name: "Kernel text not RO (best-effort)",
         func: kernel_text_ro::run,
@@ -201,9 +259,13 @@ const SCANNERS: &[Scanner] = &[
         func: sudoers::run,
     },
     Scanner {
-        name: "Kernel cmdline disables auditing/lockdown/IMA",
+        name: "Kernel cmdline and boot chain tampering",
         func: kernel_cmdline::run,
     },
+    Scanner {
+        name: "Initramfs contents",
+        func: initramfs_contents::run,
+    },
     Scanner {
         name: "Sensitive host mounts into containers",
         func: sensitive_host_mounts::run,
@@ -232,6 +294,10 @@ const SCANNERS: &[Scanner] = &[
         name: "Kernel message suppression",
         func: kernel_message_suppression::run,
     },
+    Scanner {
+        name: "Kernel hardening posture (sysctl/LSM/lockdown/Secure Boot)",
+        func: hardening_posture::run,
+    },
 ];
 
 fn main() {
@@ -0,0 +1,258 @@
+use super::ssh_footholds::{INVALID_ID, PasswdEntry, is_nologin_shell, read_passwd};
+use crate::ScanOutcome;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+};
+
+const DEFAULT_UID_MIN: u32 = 1000;
+const NOBODY_UID: u32 = 65534;
+const PRIVILEGED_GROUPS: &[&str] = &["sudo", "wheel", "admin", "docker", "disk", "lxd", "root"];
+
+struct ShadowEntry {
+    hash: String,
+    last_change_days: Option<u64>,
+}
+
+struct GroupEntry {
+    name: String,
+    gid: u32,
+    members: Vec<String>,
+}
+
+pub fn run() -> ScanOutcome {
+    let users = read_passwd()?;
+
+    let mut findings = Vec::new();
+    let mut errors = Vec::new();
+
+    let uid_min = read_uid_min();
+
+    let shadow = match read_shadow() {
+        Ok(map) => Some(map),
+        Err(err) => {
+            errors.push(err);
+            None
+        }
+    };
+
+    let groups = match read_groups() {
+        Ok(list) => list,
+        Err(err) => {
+            errors.push(err);
+            Vec::new()
+        }
+    };
+
+    let boot_day = match boot_time() {
+        Ok(btime) => Some(btime / 86400),
+        Err(err) => {
+            errors.push(err);
+            None
+        }
+    };
+
+    let mut by_uid: BTreeMap<u32, Vec<&str>> = BTreeMap::new();
+
+    for entry in &users {
+        if entry.uid == INVALID_ID {
+            findings.push(format!("user={}, issue=invalid_uid", entry.user));
+        } else {
+            by_uid
+                .entry(entry.uid)
+                .or_default()
+                .push(entry.user.as_str());
+        }
+
+        if entry.uid == 0 && entry.user != "root" {
+            findings.push(account_finding(entry, "extra_uid0"));
+        }
+
+        if entry.password.is_empty() {
+            findings.push(account_finding(entry, "empty_passwd_field"));
+        } else if entry.password != "x" && entry.password != "*" && !entry.password.starts_with('!')
+        {
+            findings.push(account_finding(entry, "hash_in_passwd"));
+        }
+
+        let system_account = entry.uid != 0 && (entry.uid < uid_min || entry.uid == NOBODY_UID);
+        if system_account && !entry.shell.is_empty() && !is_nologin_shell(&entry.shell) {
+            findings.push(format!(
+                "user={}, uid={}, shell={}, issue=system_account_login_shell",
+                entry.user, entry.uid, entry.shell
+            ));
+        }
+
+        let Some(shadow) = shadow.as_ref() else {
+            continue;
+        };
+        let Some(record) = shadow.get(&entry.user) else {
+            if entry.password == "x" {
+                findings.push(account_finding(entry, "missing_shadow_entry"));
+            }
+            continue;
+        };
+
+        if record.hash.is_empty() {
+            findings.push(account_finding(entry, "empty_password_hash"));
+        } else if system_account && has_usable_hash(&record.hash) {
+            findings.push(account_finding(entry, "system_account_password_set"));
+        }
+
+        match (record.last_change_days, boot_day) {
+            (Some(changed), Some(boot)) if changed >= boot && has_usable_hash(&record.hash) => {
+                findings.push(format!(
+                    "user={}, uid={}, last_change_day={}, boot_day={}, issue=password_changed_since_boot",
+                    entry.user, entry.uid, changed, boot
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(shadow) = shadow.as_ref() {
+        for user in shadow.keys() {
+            if !users.iter().any(|entry| &entry.user == user) {
+                findings.push(format!("user={}, issue=shadow_without_passwd", user));
+            }
+        }
+    }
+
+    for (uid, names) in &by_uid {
+        if names.len() > 1 {
+            findings.push(format!(
+                "uid={}, users={}, issue=duplicate_uid",
+                uid,
+                names.join("|")
+            ));
+        }
+    }
+
+    let mut by_gid: BTreeMap<u32, Vec<&str>> = BTreeMap::new();
+    for group in &groups {
+        by_gid
+            .entry(group.gid)
+            .or_default()
+            .push(group.name.as_str());
+    }
+    for (gid, names) in &by_gid {
+        if names.len() > 1 {
+            findings.push(format!(
+                "gid={}, groups={}, issue=duplicate_gid",
+                gid,
+                names.join("|")
+            ));
+        }
+    }
+
+    for group in &groups {
+        if !PRIVILEGED_GROUPS.contains(&group.name.as_str()) {
+            continue;
+        }
+        let mut members: BTreeSet<&str> = group.members.iter().map(String::as_str).collect();
+        for entry in &users {
+            if entry.gid == group.gid && entry.uid != 0 {
+                members.insert(entry.user.as_str());
+            }
+        }
+        members.remove("root");
+        for member in members {
+            findings.push(format!(
+                "user={}, group={}, gid={}, issue=privileged_group_member",
+                member, group.name, group.gid
+            ));
+        }
+    }
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+fn account_finding(entry: &PasswdEntry, issue: &str) -> String {
+    format!("user={}, uid={}, issue={}", entry.user, entry.uid, issue)
+}
+
+fn has_usable_hash(hash: &str) -> bool {
+    !hash.is_empty() && !hash.starts_with('!') && !hash.starts_with('*')
+}
+
+fn read_uid_min() -> u32 {
+    let Ok(content) = fs::read_to_string("/etc/login.defs") else {
+        return DEFAULT_UID_MIN;
+    };
+    content
+        .lines()
+        .find_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            match parts.as_slice() {
+                ["UID_MIN", value, ..] => value.parse().ok(),
+                _ => None,
+            }
+        })
+        .unwrap_or(DEFAULT_UID_MIN)
+}
+
+fn read_shadow() -> Result<BTreeMap<String, ShadowEntry>, String> {
+    let content = fs::read_to_string("/etc/shadow")
+        .map_err(|err| format!("failed to read /etc/shadow: {err}"))?;
+    let mut map = BTreeMap::new();
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split(':').collect();
+        if parts.len() < 3 {
+            continue;
+        }
+        map.insert(
+            parts[0].to_string(),
+            ShadowEntry {
+                hash: parts[1].to_string(),
+                last_change_days: parts[2].parse().ok(),
+            },
+        );
+    }
+    Ok(map)
+}
+
+fn read_groups() -> Result<Vec<GroupEntry>, String> {
+    let content = fs::read_to_string("/etc/group")
+        .map_err(|err| format!("failed to read /etc/group: {err}"))?;
+    let mut groups = Vec::new();
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split(':').collect();
+        if parts.len() < 4 {
+            continue;
+        }
+        let Ok(gid) = parts[2].parse() else {
+            continue;
+        };
+        groups.push(GroupEntry {
+            name: parts[0].to_string(),
+            gid,
+            members: parts[3]
+                .split(',')
+                .filter(|m| !m.is_empty())
+                .map(str::to_string)
+                .collect(),
+        });
+    }
+    Ok(groups)
+}
+
+fn boot_time() -> Result<u64, String> {
+    let stat = fs::read_to_string("/proc/stat")
+        .map_err(|err| format!("failed to read /proc/stat: {err}"))?;
+    stat.lines()
+        .find_map(|line| line.strip_prefix("btime "))
+        .and_then(|value| value.trim().parse().ok())
+        .ok_or_else(|| "btime missing from /proc/stat".to_string())
+}
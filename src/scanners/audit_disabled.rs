@@ -1,19 +1,32 @@
+use super::audit_utils::{
+    AUDIT_FILTER_EXCLUDE, AUDIT_FILTER_FS, AUDIT_FILTER_USER, AUDIT_NEVER, AuditClient, AuditRule,
+    AuditStatus,
+};
 use crate::ScanOutcome;
-use std::fs;
+use std::{fs, path::Path};
+
+const AUDIT_RULES_FILE: &str = "/etc/audit/audit.rules";
+const AUDIT_RULES_DIR: &str = "/etc/audit/rules.d";
+const MAX_LISTED_SYSCALLS: usize = 8;
+
+// Processes known to register themselves as the kernel's audit daemon.
+const AUDIT_DAEMONS: &[&str] = &["auditd", "go-audit", "auditbeat", "osqueryd", "kauditd"];
 
 pub fn run() -> ScanOutcome {
+    // Not every kernel exposes this sysctl; netlink status covers the rest.
     let enabled = match fs::read_to_string("/proc/sys/kernel/audit_enabled") {
-        Ok(value) => value.trim().to_string(),
+        Ok(value) => Some(value.trim().to_string()),
         Err(err) => {
-            if err.kind() == std::io::ErrorKind::NotFound {
-                return Ok(None);
+            if err.kind() != std::io::ErrorKind::NotFound {
+                return Err(format!("failed to read audit_enabled: {err}"));
             }
-            return Err(format!("failed to read audit_enabled: {err}"));
+            None
         }
     };
 
     let mut findings = Vec::new();
-    if enabled == "0" {
+    let mut errors = Vec::new();
+    if enabled.as_deref() == Some("0") {
         findings.push("enabled=0".to_string());
     }
 
@@ -36,6 +49,33 @@ pub fn run() -> ScanOutcome {
         }
     }
 
+    match AuditClient::connect() {
+        Ok(mut client) => {
+            match client.status() {
+                Ok(status) => {
+                    backlog_limit = Some(status.backlog_limit as u64);
+                    inspect_status(&status, enabled.as_deref(), &mut findings);
+                }
+                Err(err) => errors.push(format!("AUDIT_GET: {err}")),
+            }
+            match client.rules() {
+                Ok(rules) => {
+                    for (idx, rule) in rules.iter().enumerate() {
+                        if let Some(finding) = inspect_rule(idx, rule) {
+                            findings.push(finding);
+                        }
+                    }
+                }
+                Err(err) => errors.push(format!("AUDIT_LIST_RULES: {err}")),
+            }
+        }
+        // Without audit support in the kernel there is nothing to inspect.
+        Err(_) if enabled.is_none() && !Path::new("/proc/self/loginuid").exists() => {
+            return Ok(None);
+        }
+        Err(err) => errors.push(err),
+    }
+
     const MIN_BACKLOG_LIMIT: u64 = 32;
     if let Some(limit) = backlog_limit {
         if limit < MIN_BACKLOG_LIMIT {
@@ -44,8 +84,166 @@ pub fn run() -> ScanOutcome {
     }
 
     if findings.is_empty() {
-        Ok(None)
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+fn inspect_status(status: &AuditStatus, sysctl_enabled: Option<&str>, findings: &mut Vec<String>) {
+    if status.enabled == 0 && sysctl_enabled != Some("0") {
+        findings.push("enabled=0".to_string());
+    }
+    if status.lost != 0 {
+        findings.push(format!("lost_events={}", status.lost));
+    }
+    if status.failure == 0 {
+        findings.push("failure_mode=silent".to_string());
+    }
+    if status.rate_limit != 0 {
+        findings.push(format!(
+            "rate_limit={}/s, backlog={}",
+            status.rate_limit, status.backlog
+        ));
+    }
+
+    if status.pid == 0 {
+        if status.enabled != 0 {
+            findings.push("auditd_pid=0, records_go_to_kmsg".to_string());
+        }
+    } else {
+        match fs::read_to_string(format!("/proc/{}/comm", status.pid)) {
+            Ok(comm) => {
+                let comm = comm.trim();
+                if !AUDIT_DAEMONS.contains(&comm) {
+                    findings.push(format!(
+                        "auditd_pid={}, comm={}, issue=unexpected_audit_daemon",
+                        status.pid, comm
+                    ));
+                }
+            }
+            Err(_) => findings.push(format!(
+                "auditd_pid={}, issue=audit_daemon_missing",
+                status.pid
+            )),
+        }
+    }
+
+    match configured_immutable() {
+        Some(source) if status.enabled != 2 => findings.push(format!(
+            "enabled={}, configured_immutable={}, issue=immutable_not_applied",
+            status.enabled, source
+        )),
+        _ => {}
+    }
+}
+
+/// Flags rules that carve blind spots: never-actions on syscall lists,
+/// record type exclusions, and user/filesystem message suppression.
+fn inspect_rule(idx: usize, rule: &AuditRule) -> Option<String> {
+    let mut issues = Vec::new();
+
+    match rule.list {
+        AUDIT_FILTER_EXCLUDE => issues.push("excludes_record_types".to_string()),
+        AUDIT_FILTER_USER if rule.action == AUDIT_NEVER => {
+            issues.push("excludes_user_messages".to_string())
+        }
+        AUDIT_FILTER_FS if rule.action == AUDIT_NEVER => {
+            issues.push("excludes_filesystem".to_string())
+        }
+        _ if rule.action == AUDIT_NEVER => {
+            for field in &rule.fields {
+                let issue = match field.name.as_str() {
+                    "uid" | "euid" | "suid" | "fsuid" | "auid" | "gid" | "egid" | "sessionid"
+                    | "loginuid_set" => "excludes_user",
+                    "exe" => "excludes_exe",
+                    "pid" | "ppid" => "excludes_process",
+                    "path" | "dir" => "excludes_path",
+                    "subj_user" | "subj_role" | "subj_type" => "excludes_label",
+                    _ => continue,
+                };
+                if !issues.iter().any(|existing| existing == issue) {
+                    issues.push(issue.to_string());
+                }
+            }
+            if issues.is_empty() {
+                issues.push(if rule.all_syscalls {
+                    "excludes_all_syscalls".to_string()
+                } else {
+                    "excludes_syscalls".to_string()
+                });
+            }
+        }
+        _ => {}
+    }
+
+    if issues.is_empty() {
+        return None;
+    }
+
+    let syscalls = if rule.all_syscalls {
+        "all".to_string()
+    } else if rule.syscalls.is_empty() {
+        "none".to_string()
     } else {
-        Ok(Some(findings.join(", ")))
+        let mut listed: Vec<String> = rule
+            .syscalls
+            .iter()
+            .take(MAX_LISTED_SYSCALLS)
+            .map(ToString::to_string)
+            .collect();
+        if rule.syscalls.len() > MAX_LISTED_SYSCALLS {
+            listed.push(format!("+{}", rule.syscalls.len() - MAX_LISTED_SYSCALLS));
+        }
+        listed.join("|")
+    };
+    let fields: Vec<String> = rule
+        .fields
+        .iter()
+        .map(|field| format!("{}{}{}", field.name, field.op, field.value))
+        .collect();
+
+    Some(format!(
+        "rule={}, list={}, action={}, syscalls={}, fields={}, issues={}",
+        idx,
+        rule.list_name(),
+        rule.action_name(),
+        syscalls,
+        if fields.is_empty() {
+            "-".to_string()
+        } else {
+            fields.join("|")
+        },
+        issues.join(",")
+    ))
+}
+
+/// Returns the rules file that asks for `-e 2`, if any.
+fn configured_immutable() -> Option<String> {
+    let mut files = vec![AUDIT_RULES_FILE.to_string()];
+    if let Ok(entries) = fs::read_dir(AUDIT_RULES_DIR) {
+        let mut extra: Vec<String> = entries
+            .flatten()
+            .map(|entry| entry.path().to_string_lossy().into_owned())
+            .filter(|path| path.ends_with(".rules"))
+            .collect();
+        extra.sort();
+        files.extend(extra);
     }
+
+    files.into_iter().find(|path| {
+        fs::read_to_string(path).is_ok_and(|content| {
+            content.lines().any(|line| {
+                let tokens: Vec<&str> = line.split_whitespace().collect();
+                tokens.first() != Some(&"#") && tokens.windows(2).any(|w| w == ["-e", "2"])
+            })
+        })
+    })
 }
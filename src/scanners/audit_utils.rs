@@ -0,0 +1,376 @@
+use std::{
+    io, mem,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+};
+
+const NETLINK_AUDIT: libc::c_int = 9;
+const AUDIT_GET: u16 = 1000;
+const AUDIT_LIST_RULES: u16 = 1013;
+const NLMSG_HDRLEN: usize = 16;
+const RECV_TIMEOUT_SECS: libc::time_t = 2;
+
+// Offsets into struct audit_rule_data.
+const AUDIT_BITMASK_SIZE: usize = 64;
+const AUDIT_MAX_FIELDS: usize = 64;
+const RULE_HEADER_WORDS: usize = 3 + AUDIT_BITMASK_SIZE + 3 * AUDIT_MAX_FIELDS + 1;
+
+pub const AUDIT_FILTER_USER: u32 = 0x00;
+pub const AUDIT_FILTER_TASK: u32 = 0x01;
+pub const AUDIT_FILTER_ENTRY: u32 = 0x02;
+pub const AUDIT_FILTER_EXIT: u32 = 0x04;
+pub const AUDIT_FILTER_EXCLUDE: u32 = 0x05;
+pub const AUDIT_FILTER_FS: u32 = 0x06;
+pub const AUDIT_FILTER_URING_EXIT: u32 = 0x07;
+pub const AUDIT_NEVER: u32 = 0;
+
+pub struct AuditStatus {
+    pub enabled: u32,
+    pub failure: u32,
+    pub pid: u32,
+    pub rate_limit: u32,
+    pub backlog_limit: u32,
+    pub lost: u32,
+    pub backlog: u32,
+}
+
+pub struct AuditField {
+    pub name: String,
+    pub op: &'static str,
+    pub value: String,
+}
+
+pub struct AuditRule {
+    pub list: u32,
+    pub action: u32,
+    pub syscalls: Vec<u32>,
+    pub all_syscalls: bool,
+    pub fields: Vec<AuditField>,
+}
+
+impl AuditRule {
+    pub fn list_name(&self) -> &'static str {
+        match self.list {
+            AUDIT_FILTER_USER => "user",
+            AUDIT_FILTER_TASK => "task",
+            AUDIT_FILTER_ENTRY => "entry",
+            AUDIT_FILTER_EXIT => "exit",
+            AUDIT_FILTER_EXCLUDE => "exclude",
+            AUDIT_FILTER_FS => "filesystem",
+            AUDIT_FILTER_URING_EXIT => "io_uring",
+            _ => "unknown",
+        }
+    }
+
+    pub fn action_name(&self) -> &'static str {
+        match self.action {
+            AUDIT_NEVER => "never",
+            1 => "possible",
+            2 => "always",
+            _ => "unknown",
+        }
+    }
+}
+
+/// Minimal NETLINK_AUDIT client speaking the same requests auditctl -s and
+/// auditctl -l issue.
+pub struct AuditClient {
+    fd: OwnedFd,
+    seq: u32,
+}
+
+impl AuditClient {
+    pub fn connect() -> Result<Self, String> {
+        let raw = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                NETLINK_AUDIT,
+            )
+        };
+        if raw < 0 {
+            return Err(format!(
+                "failed to open NETLINK_AUDIT socket: {}",
+                io::Error::last_os_error()
+            ));
+        }
+        let fd = unsafe { OwnedFd::from_raw_fd(raw) };
+
+        let timeout = libc::timeval {
+            tv_sec: RECV_TIMEOUT_SECS,
+            tv_usec: 0,
+        };
+        let ret = unsafe {
+            libc::setsockopt(
+                fd.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_RCVTIMEO,
+                &timeout as *const libc::timeval as *const libc::c_void,
+                mem::size_of::<libc::timeval>() as libc::socklen_t,
+            )
+        };
+        if ret < 0 {
+            return Err(format!(
+                "failed to set audit socket timeout: {}",
+                io::Error::last_os_error()
+            ));
+        }
+
+        Ok(Self { fd, seq: 0 })
+    }
+
+    pub fn status(&mut self) -> Result<AuditStatus, String> {
+        let seq = self.send(AUDIT_GET)?;
+        loop {
+            for (kind, payload) in self.receive(seq)? {
+                if kind != AUDIT_GET {
+                    continue;
+                }
+                let word = |idx: usize| read_u32(&payload, idx * 4).unwrap_or(0);
+                if payload.len() < 32 {
+                    return Err("short AUDIT_GET reply".to_string());
+                }
+                return Ok(AuditStatus {
+                    enabled: word(1),
+                    failure: word(2),
+                    pid: word(3),
+                    rate_limit: word(4),
+                    backlog_limit: word(5),
+                    lost: word(6),
+                    backlog: word(7),
+                });
+            }
+        }
+    }
+
+    pub fn rules(&mut self) -> Result<Vec<AuditRule>, String> {
+        let seq = self.send(AUDIT_LIST_RULES)?;
+        let mut rules = Vec::new();
+        loop {
+            for (kind, payload) in self.receive(seq)? {
+                match kind {
+                    AUDIT_LIST_RULES => {
+                        if let Some(rule) = parse_rule(&payload) {
+                            rules.push(rule);
+                        }
+                    }
+                    kind if kind == libc::NLMSG_DONE as u16 => return Ok(rules),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn send(&mut self, kind: u16) -> Result<u32, String> {
+        self.seq += 1;
+        let mut message = Vec::with_capacity(NLMSG_HDRLEN);
+        message.extend_from_slice(&(NLMSG_HDRLEN as u32).to_ne_bytes());
+        message.extend_from_slice(&kind.to_ne_bytes());
+        message.extend_from_slice(&(libc::NLM_F_REQUEST as u16).to_ne_bytes());
+        message.extend_from_slice(&self.seq.to_ne_bytes());
+        message.extend_from_slice(&0u32.to_ne_bytes());
+
+        let mut addr: libc::sockaddr_nl = unsafe { mem::zeroed() };
+        addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        let sent = unsafe {
+            libc::sendto(
+                self.fd.as_raw_fd(),
+                message.as_ptr() as *const libc::c_void,
+                message.len(),
+                0,
+                &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        };
+        if sent < 0 {
+            return Err(format!(
+                "failed to send audit request {kind}: {}",
+                io::Error::last_os_error()
+            ));
+        }
+        Ok(self.seq)
+    }
+
+    /// Reads one datagram and returns the messages that answer `seq`.
+    fn receive(&self, seq: u32) -> Result<Vec<(u16, Vec<u8>)>, String> {
+        let mut buf = vec![0u8; 64 * 1024];
+        let len = unsafe {
+            libc::recv(
+                self.fd.as_raw_fd(),
+                buf.as_mut_ptr() as *mut libc::c_void,
+                buf.len(),
+                0,
+            )
+        };
+        if len < 0 {
+            return Err(format!(
+                "failed to receive audit reply: {}",
+                io::Error::last_os_error()
+            ));
+        }
+        buf.truncate(len as usize);
+
+        let mut messages = Vec::new();
+        let mut offset = 0;
+        while offset + NLMSG_HDRLEN <= buf.len() {
+            let msg_len = read_u32(&buf, offset).unwrap_or(0) as usize;
+            if msg_len < NLMSG_HDRLEN || offset + msg_len > buf.len() {
+                break;
+            }
+            let kind = u16::from_ne_bytes([buf[offset + 4], buf[offset + 5]]);
+            let msg_seq = read_u32(&buf, offset + 8).unwrap_or(0);
+            let payload = buf[offset + NLMSG_HDRLEN..offset + msg_len].to_vec();
+            offset += (msg_len + 3) & !3;
+
+            if msg_seq != seq {
+                continue;
+            }
+            if kind == libc::NLMSG_ERROR as u16 {
+                let code = read_u32(&payload, 0).unwrap_or(0) as i32;
+                if code != 0 {
+                    return Err(format!(
+                        "audit request rejected: {}",
+                        io::Error::from_raw_os_error(-code)
+                    ));
+                }
+                continue;
+            }
+            messages.push((kind, payload));
+        }
+        Ok(messages)
+    }
+}
+
+fn parse_rule(payload: &[u8]) -> Option<AuditRule> {
+    let word = |idx: usize| read_u32(payload, idx * 4);
+    // The flags word carries the filter list plus AUDIT_FILTER_PREPEND.
+    let list = word(0)? & 0x0f;
+    let action = word(1)?;
+    let field_count = (word(2)? as usize).min(AUDIT_MAX_FIELDS);
+
+    let mut syscalls = Vec::new();
+    let mut all_syscalls = true;
+    for idx in 0..AUDIT_BITMASK_SIZE {
+        let mask = word(3 + idx)?;
+        // The kernel folds the syscall class bits of the last word away.
+        if mask != u32::MAX && idx < AUDIT_BITMASK_SIZE - 1 {
+            all_syscalls = false;
+        }
+        for bit in 0..32 {
+            if mask & (1 << bit) != 0 {
+                syscalls.push((idx * 32 + bit) as u32);
+            }
+        }
+    }
+
+    let fields_base = 3 + AUDIT_BITMASK_SIZE;
+    let values_base = fields_base + AUDIT_MAX_FIELDS;
+    let flags_base = values_base + AUDIT_MAX_FIELDS;
+    let buf = payload.get(RULE_HEADER_WORDS * 4..).unwrap_or(&[]);
+    let mut buf_offset = 0usize;
+
+    let mut fields = Vec::new();
+    for idx in 0..field_count {
+        let id = word(fields_base + idx)?;
+        let value = word(values_base + idx)?;
+        let op = operator(word(flags_base + idx)?);
+        let value = if is_string_field(id) {
+            let end = (buf_offset + value as usize).min(buf.len());
+            let text = String::from_utf8_lossy(&buf[buf_offset.min(end)..end]).into_owned();
+            buf_offset = end;
+            text
+        } else if matches!(id, 1..=9 | 109 | 110) && value == u32::MAX {
+            "unset".to_string()
+        } else {
+            value.to_string()
+        };
+        fields.push(AuditField {
+            name: field_name(id),
+            op,
+            value,
+        });
+    }
+
+    Some(AuditRule {
+        list,
+        action,
+        syscalls,
+        all_syscalls,
+        fields,
+    })
+}
+
+fn operator(flags: u32) -> &'static str {
+    match flags {
+        0x0800_0000 => "&",
+        0x1000_0000 => "<",
+        0x2000_0000 => ">",
+        0x3000_0000 => "!=",
+        0x4000_0000 => "=",
+        0x4800_0000 => "&=",
+        0x5000_0000 => "<=",
+        0x6000_0000 => ">=",
+        _ => "?",
+    }
+}
+
+fn is_string_field(id: u32) -> bool {
+    matches!(id, 13..=17 | 19..=23 | 105 | 107 | 112 | 210)
+}
+
+fn field_name(id: u32) -> String {
+    let name = match id {
+        0 => "pid",
+        1 => "uid",
+        2 => "euid",
+        3 => "suid",
+        4 => "fsuid",
+        5 => "gid",
+        6 => "egid",
+        7 => "sgid",
+        8 => "fsgid",
+        9 => "auid",
+        10 => "pers",
+        11 => "arch",
+        12 => "msgtype",
+        13 => "subj_user",
+        14 => "subj_role",
+        15 => "subj_type",
+        16 => "subj_sen",
+        17 => "subj_clr",
+        18 => "ppid",
+        19 => "obj_user",
+        20 => "obj_role",
+        21 => "obj_type",
+        22 => "obj_lev_low",
+        23 => "obj_lev_high",
+        24 => "loginuid_set",
+        25 => "sessionid",
+        26 => "fstype",
+        100 => "devmajor",
+        101 => "devminor",
+        102 => "inode",
+        103 => "exit",
+        104 => "success",
+        105 => "path",
+        106 => "perm",
+        107 => "dir",
+        108 => "filetype",
+        109 => "obj_uid",
+        110 => "obj_gid",
+        111 => "field_compare",
+        112 => "exe",
+        113 => "saddr_fam",
+        200 => "a0",
+        201 => "a1",
+        202 => "a2",
+        203 => "a3",
+        210 => "key",
+        other => return format!("field{other}"),
+    };
+    name.to_string()
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> Option<u32> {
+    let bytes = buf.get(offset..offset + 4)?;
+    Some(u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
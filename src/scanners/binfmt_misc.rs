@@ -0,0 +1,237 @@
+use super::modprobe_hotplug::evaluate_helper;
+use crate::ScanOutcome;
+use std::{fs, io::Read};
+
+const BINFMT_MISC_DIR: &str = "/proc/sys/fs/binfmt_misc";
+// The kernel matches magic against the first BINPRM_BUF_SIZE bytes.
+const ELF_SAMPLE_LEN: u64 = 256;
+
+// Scripts every system runs; a handler matching them sees each invocation.
+const SHEBANG_SAMPLES: &[&[u8]] = &[b"#!/bin/sh\n", b"#! /bin/sh\n", b"#!/usr/bin/env python3\n"];
+const COMMON_SCRIPT_EXTENSIONS: &[&str] = &["sh", "bash", "py", "pl", "rb", "php", "js", "lua"];
+
+enum Matcher {
+    Magic {
+        offset: usize,
+        magic: Vec<u8>,
+        mask: Option<Vec<u8>>,
+    },
+    Extension(String),
+}
+
+impl Matcher {
+    fn matches(&self, sample: &[u8]) -> bool {
+        let Matcher::Magic {
+            offset,
+            magic,
+            mask,
+        } = self
+        else {
+            return false;
+        };
+        let Some(window) = sample.get(*offset..offset + magic.len()) else {
+            return false;
+        };
+        window
+            .iter()
+            .zip(magic)
+            .enumerate()
+            .all(|(idx, (byte, want))| {
+                let mask = mask
+                    .as_ref()
+                    .and_then(|mask| mask.get(idx).copied())
+                    .unwrap_or(0xff);
+                byte & mask == want & mask
+            })
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Matcher::Magic {
+                offset,
+                magic,
+                mask,
+            } => {
+                let mut text = format!("magic@{}={}", offset, hex(magic));
+                if let Some(mask) = mask {
+                    text.push_str(&format!("/{}", hex(mask)));
+                }
+                text
+            }
+            Matcher::Extension(ext) => format!("extension=.{ext}"),
+        }
+    }
+}
+
+struct Handler {
+    name: String,
+    enabled: bool,
+    interpreter: String,
+    flags: String,
+    matcher: Option<Matcher>,
+}
+
+pub fn run() -> ScanOutcome {
+    // A globally disabled binfmt_misc ignores every registered handler.
+    match fs::read_to_string(format!("{BINFMT_MISC_DIR}/status")) {
+        Ok(status) if status.trim() == "disabled" => return Ok(None),
+        Ok(_) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(format!("failed to read {BINFMT_MISC_DIR}/status: {err}")),
+    }
+
+    let entries = fs::read_dir(BINFMT_MISC_DIR)
+        .map_err(|err| format!("failed to read {BINFMT_MISC_DIR}: {err}"))?;
+
+    let mut findings = Vec::new();
+    let mut errors = Vec::new();
+    let native_elf = native_elf_sample(&mut errors);
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name == "register" || name == "status" {
+            continue;
+        }
+        let handler = match fs::read_to_string(entry.path()) {
+            Ok(content) => parse_handler(&name, &content),
+            Err(err) => {
+                errors.push(format!("failed to read {name}: {err}"));
+                continue;
+            }
+        };
+        if !handler.enabled {
+            continue;
+        }
+
+        let mut issues: Vec<&str> = Vec::new();
+        match &handler.matcher {
+            Some(matcher @ Matcher::Magic { .. }) => {
+                if native_elf
+                    .as_deref()
+                    .is_some_and(|elf| matcher.matches(elf))
+                {
+                    issues.push("matches_native_elf");
+                }
+                if SHEBANG_SAMPLES.iter().any(|sample| matcher.matches(sample)) {
+                    issues.push("matches_shebang");
+                }
+            }
+            Some(Matcher::Extension(ext)) if COMMON_SCRIPT_EXTENSIONS.contains(&ext.as_str()) => {
+                issues.push("matches_common_script_extension");
+            }
+            _ => {}
+        }
+
+        let label = format!("binfmt_misc:{}", handler.name);
+        issues.extend(evaluate_helper(&handler.interpreter, &label, &mut errors));
+        // With F the interpreter was opened at registration, so a missing
+        // path means the kernel keeps running a deleted file.
+        if handler.flags.contains('F') && issues.contains(&"missing_target") {
+            issues.retain(|issue| *issue != "missing_target");
+            issues.push("fixed_interpreter_deleted");
+        }
+        if handler.flags.contains('C') {
+            issues.push("credentials_flag");
+        }
+
+        if !issues.is_empty() {
+            findings.push(format!(
+                "handler={}, {}, interpreter={}, flags={}, issues={}",
+                handler.name,
+                handler
+                    .matcher
+                    .as_ref()
+                    .map(Matcher::describe)
+                    .unwrap_or_else(|| "match=unknown".to_string()),
+                handler.interpreter,
+                if handler.flags.is_empty() {
+                    "-"
+                } else {
+                    handler.flags.as_str()
+                },
+                issues.join("|")
+            ));
+        }
+    }
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+/// Parses the `enabled`/`interpreter`/`flags`/`offset`/`magic`/`mask` lines of a handler.
+fn parse_handler(name: &str, content: &str) -> Handler {
+    let mut handler = Handler {
+        name: name.to_string(),
+        enabled: content.lines().next() == Some("enabled"),
+        interpreter: String::new(),
+        flags: String::new(),
+        matcher: None,
+    };
+    let mut offset = 0;
+    let mut magic = None;
+    let mut mask = None;
+
+    for line in content.lines() {
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        match key {
+            "interpreter" => handler.interpreter = value.trim().to_string(),
+            "flags:" => handler.flags = value.trim().to_string(),
+            "offset" => offset = value.trim().parse().unwrap_or(0),
+            "magic" => magic = decode_hex(value.trim()),
+            "mask" => mask = decode_hex(value.trim()),
+            "extension" => {
+                handler.matcher = Some(Matcher::Extension(
+                    value.trim().trim_start_matches('.').to_string(),
+                ))
+            }
+            _ => {}
+        }
+    }
+    if let Some(magic) = magic {
+        handler.matcher = Some(Matcher::Magic {
+            offset,
+            magic,
+            mask,
+        });
+    }
+    handler
+}
+
+/// The header of our own executable stands in for any native binary.
+fn native_elf_sample(errors: &mut Vec<String>) -> Option<Vec<u8>> {
+    let mut sample = Vec::new();
+    match fs::File::open("/proc/self/exe")
+        .and_then(|file| file.take(ELF_SAMPLE_LEN).read_to_end(&mut sample))
+    {
+        Ok(_) => Some(sample),
+        Err(err) => {
+            errors.push(format!("failed to read /proc/self/exe: {err}"));
+            None
+        }
+    }
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|idx| u8::from_str_radix(&text[idx..idx + 2], 16).ok())
+        .collect()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
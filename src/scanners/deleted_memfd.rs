@@ -1,9 +1,89 @@
 use crate::ScanOutcome;
-use std::{fs, io, path::Path};
+use sha2::{Digest, Sha256};
+use std::{
+    env, fs,
+    io::{self, Read, Write},
+    os::unix::fs::{DirBuilderExt, FileExt, OpenOptionsExt},
+    path::Path,
+};
+
+/// Directory to copy fileless payloads into; carving is disabled when unset.
+const CARVE_DIR_ENV: &str = "GHOSTSCAN_CARVE_DIR";
+const CARVE_LIMIT: u64 = 64 << 20;
+const AT_EXECFN: u64 = 31;
+const TMPFS_TYPES: &[&str] = &["tmpfs", "ramfs", "hugetlbfs"];
+const SHM_PREFIXES: &[&str] = &["/dev/shm/", "/run/shm/"];
+const FD_EXEC_PREFIXES: &[&str] = &["/dev/fd/", "/proc/self/fd/", "/proc/thread-self/fd/"];
+const LOADERS: &[&str] = &["ld-linux", "ld.so", "ld-musl", "ld64.so"];
+const INTERPRETERS: &[&str] = &[
+    "sh", "bash", "dash", "zsh", "ksh", "busybox", "python", "perl", "ruby", "php", "node", "lua",
+];
+
+/// Command-line options of an interpreter family that change what the
+/// first operand means.
+struct InterpreterOptions {
+    names: &'static [&'static str],
+    /// Options whose argument is inline code; no script file is run.
+    inline: &'static [&'static str],
+    /// Options that consume the following argument.
+    valued: &'static [&'static str],
+}
+
+const INTERPRETER_OPTIONS: &[InterpreterOptions] = &[
+    InterpreterOptions {
+        names: &["sh", "bash", "dash", "zsh", "ksh"],
+        inline: &["-c"],
+        valued: &["-o", "-O", "+o", "+O", "--rcfile", "--init-file"],
+    },
+    InterpreterOptions {
+        names: &["python"],
+        inline: &["-c", "-m"],
+        valued: &["-W", "-X", "-Q"],
+    },
+    InterpreterOptions {
+        names: &["perl"],
+        inline: &["-e", "-E"],
+        valued: &["-I"],
+    },
+    InterpreterOptions {
+        names: &["ruby"],
+        inline: &["-e"],
+        valued: &["-I", "-r", "-C", "-E"],
+    },
+    InterpreterOptions {
+        names: &["php"],
+        inline: &["-r", "-R", "-B", "-E"],
+        valued: &["-c", "-d", "-z"],
+    },
+    InterpreterOptions {
+        names: &["node"],
+        inline: &["-e", "-p", "--eval", "--print"],
+        valued: &[
+            "-r",
+            "--require",
+            "--import",
+            "--loader",
+            "-C",
+            "--conditions",
+        ],
+    },
+    InterpreterOptions {
+        names: &["lua"],
+        inline: &["-e"],
+        valued: &["-l"],
+    },
+];
+
+struct Mount {
+    point: String,
+    fstype: String,
+    noexec: bool,
+}
 
 pub fn run() -> ScanOutcome {
     let mut findings = Vec::new();
     let mut errors = Vec::new();
+    let carve_dir = env::var(CARVE_DIR_ENV).ok().filter(|dir| !dir.is_empty());
 
     match fs::read_dir("/proc") {
         Ok(entries) => {
@@ -11,9 +91,10 @@ pub fn run() -> ScanOutcome {
                 let name = entry.file_name();
                 let pid_str = name.to_string_lossy();
                 if let Ok(pid) = pid_str.parse::<i32>() {
-                    match inspect_process(pid) {
+                    match inspect_process(pid, carve_dir.as_deref()) {
                         Ok(Some(record)) => findings.push(record),
                         Ok(None) => {}
+                        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
                         Err(err) => errors.push(format!("pid={pid}: {err}")),
                     }
                 }
@@ -37,47 +118,335 @@ pub fn run() -> ScanOutcome {
     }
 }
 
-fn inspect_process(pid: i32) -> io::Result<Option<String>> {
+fn inspect_process(pid: i32, carve_dir: Option<&str>) -> io::Result<Option<String>> {
     let proc_dir = Path::new("/proc").join(pid.to_string());
 
-    let exe_path = fs::read_link(proc_dir.join("exe"))
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|_| "unknown".to_string());
-
-    let indicator = if exe_path.contains("(deleted)") {
-        Some(exe_path.clone())
-    } else if exe_path.starts_with("memfd:") || exe_path.contains("memfd:") {
-        Some(exe_path.clone())
-    } else {
-        None
-    };
-
-    let Some(exe) = indicator else {
+    let Ok(exe_link) = fs::read_link(proc_dir.join("exe")) else {
         return Ok(None);
     };
+    let exe_path = exe_link.to_string_lossy().to_string();
 
     let comm = fs::read_to_string(proc_dir.join("comm"))?
         .trim()
         .to_string();
-    let cwd = fs::read_link(proc_dir.join("cwd"))
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|_| "unknown".to_string());
-    let cmdline = fs::read(proc_dir.join("cmdline"))
+    let argv: Vec<String> = fs::read(proc_dir.join("cmdline"))
         .unwrap_or_default()
         .split(|b| *b == 0)
         .filter(|segment| !segment.is_empty())
         .map(|segment| String::from_utf8_lossy(segment).into_owned())
-        .collect::<Vec<_>>()
-        .join(" ");
+        .collect();
 
-    let cmdline = if cmdline.is_empty() {
-        "".to_string()
-    } else {
-        cmdline
+    let mut indicators = Vec::new();
+    let mut carve_targets = Vec::new();
+
+    if exe_path.contains("memfd:") {
+        indicators.push("exe_memfd".to_string());
+        carve_targets.push(("exe".to_string(), proc_dir.join("exe")));
+    } else if exe_path.contains("(deleted)") {
+        indicators.push("exe_deleted".to_string());
+        carve_targets.push(("exe".to_string(), proc_dir.join("exe")));
+    }
+
+    // fexecve()/execveat(AT_EMPTY_PATH) name the program after the fd.
+    if let Some(execfn) = read_execfn(&proc_dir) {
+        if FD_EXEC_PREFIXES
+            .iter()
+            .any(|prefix| execfn.starts_with(prefix))
+            || (execfn.starts_with("/proc/") && execfn.contains("/fd/"))
+        {
+            indicators.push(format!("exec_via_fd({execfn})"));
+        }
+    } else if !comm.is_empty() && comm.chars().all(|c| c.is_ascii_digit()) {
+        indicators.push(format!("exec_via_fd(comm={comm})"));
+    }
+
+    let mounts = read_mounts(&proc_dir);
+    let exe_clean = exe_path.trim_end_matches(" (deleted)");
+    if exe_clean.starts_with('/') && !exe_path.contains("memfd:") {
+        let volatile = if SHM_PREFIXES
+            .iter()
+            .any(|prefix| exe_clean.starts_with(prefix))
+        {
+            Some("exe_shm".to_string())
+        } else {
+            match mount_for(&mounts, exe_clean) {
+                Some(mount) if TMPFS_TYPES.contains(&mount.fstype.as_str()) => {
+                    Some(format!("exe_{}({})", mount.fstype, mount.point))
+                }
+                _ => None,
+            }
+        };
+        if let Some(indicator) = volatile {
+            indicators.push(indicator);
+            if carve_targets.is_empty() {
+                carve_targets.push(("exe".to_string(), proc_dir.join("exe")));
+            }
+        }
+    }
+
+    // Running a binary through the dynamic loader sidesteps noexec mounts.
+    let exe_base = basename(exe_clean);
+    if LOADERS.iter().any(|loader| exe_base.starts_with(loader)) {
+        let target = argv
+            .iter()
+            .skip(1)
+            .find(|arg| !arg.starts_with('-'))
+            .cloned()
+            .unwrap_or_default();
+        let on_noexec = target.starts_with('/')
+            && mount_for(&mounts, &target).is_some_and(|mount| mount.noexec);
+        indicators.push(format!(
+            "loader_exec({}{})",
+            target,
+            if on_noexec { ",noexec_bypass" } else { "" }
+        ));
+    }
+
+    let maps = fs::read_to_string(proc_dir.join("maps")).unwrap_or_default();
+    let interpreter = is_interpreter(&comm) || is_interpreter(exe_base);
+
+    if let Ok(entries) = fs::read_dir(proc_dir.join("fd")) {
+        for entry in entries.flatten() {
+            let fd = entry.file_name().to_string_lossy().to_string();
+            let Ok(target) = fs::read_link(entry.path()) else {
+                continue;
+            };
+            let target = target.to_string_lossy().to_string();
+
+            if target.starts_with("/memfd:") {
+                let memfd = target.trim_end_matches(" (deleted)");
+                if !maps.contains(memfd) && has_executable_magic(&entry.path()) {
+                    indicators.push(format!("memfd_unmapped(fd={fd},{memfd})"));
+                    carve_targets.push((format!("fd{fd}"), entry.path()));
+                }
+                continue;
+            }
+
+            if !interpreter {
+                continue;
+            }
+            if fd == "0" && target.starts_with("pipe:") && reads_script_from_stdin(&argv) {
+                indicators.push("script_from_pipe(fd=0)".to_string());
+                continue;
+            }
+            let Some(path) = target.strip_suffix(" (deleted)") else {
+                continue;
+            };
+            // bash keeps its script on fd 255; other interpreters hold it open by path.
+            let script = fd == "255" || argv.iter().skip(1).any(|arg| arg == path);
+            if script {
+                indicators.push(format!("deleted_script(fd={fd},{path})"));
+                carve_targets.push((format!("fd{fd}"), entry.path()));
+            }
+        }
+    }
+
+    if interpreter {
+        for arg in argv.iter().skip(1) {
+            if FD_EXEC_PREFIXES
+                .iter()
+                .any(|prefix| arg.starts_with(prefix))
+            {
+                indicators.push(format!("script_from_fd({arg})"));
+            }
+        }
+        let options = interpreter_options(exe_base).or_else(|| interpreter_options(&comm));
+        if let Some(script) = options.and_then(|options| script_argument(&argv, options)) {
+            let resolved = if script.starts_with('/') {
+                proc_dir.join("root").join(script.trim_start_matches('/'))
+            } else {
+                proc_dir.join("cwd").join(script)
+            };
+            if !resolved.exists() && !indicators.iter().any(|i| i.contains(script)) {
+                indicators.push(format!("script_missing({script})"));
+            }
+        }
+    }
+
+    if indicators.is_empty() {
+        return Ok(None);
+    }
+
+    let cwd = fs::read_link(proc_dir.join("cwd"))
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "unknown".to_string());
+
+    let mut record = format!(
+        "pid={}, comm={}, exe={}, cwd={}, cmdline={}, indicators={}",
+        pid,
+        comm,
+        exe_path,
+        cwd,
+        argv.join(" "),
+        indicators.join(",")
+    );
+
+    if let Some(dir) = carve_dir {
+        for (label, source) in &carve_targets {
+            match carve(dir, pid, label, source) {
+                Ok(summary) => record.push_str(&format!(", {summary}")),
+                Err(err) => record.push_str(&format!(", carve_{label}_error={err}")),
+            }
+        }
+    }
+
+    Ok(Some(record))
+}
+
+fn read_execfn(proc_dir: &Path) -> Option<String> {
+    let auxv = fs::read(proc_dir.join("auxv")).ok()?;
+    let addr = auxv.chunks_exact(16).find_map(|pair| {
+        let key = u64::from_ne_bytes(pair[..8].try_into().ok()?);
+        let value = u64::from_ne_bytes(pair[8..].try_into().ok()?);
+        (key == AT_EXECFN).then_some(value)
+    })?;
+    let mem = fs::File::open(proc_dir.join("mem")).ok()?;
+    let mut buf = [0u8; 256];
+    let read = mem.read_at(&mut buf, addr).ok()?;
+    let end = buf[..read].iter().position(|b| *b == 0)?;
+    Some(String::from_utf8_lossy(&buf[..end]).into_owned())
+}
+
+fn read_mounts(proc_dir: &Path) -> Vec<Mount> {
+    let Ok(content) = fs::read_to_string(proc_dir.join("mountinfo")) else {
+        return Vec::new();
     };
+    content
+        .lines()
+        .filter_map(|line| {
+            let (left, right) = line.split_once(" - ")?;
+            let fields: Vec<&str> = left.split_whitespace().collect();
+            let fstype = right.split_whitespace().next()?;
+            Some(Mount {
+                point: unescape_mount_path(fields.get(4)?),
+                fstype: fstype.to_string(),
+                noexec: fields.get(5)?.split(',').any(|opt| opt == "noexec"),
+            })
+        })
+        .collect()
+}
+
+fn unescape_mount_path(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let octal = bytes.get(idx + 1..idx + 4).filter(|digits| {
+            bytes[idx] == b'\\' && digits.iter().all(|d| (b'0'..=b'7').contains(d))
+        });
+        match octal {
+            Some(digits) => {
+                out.push(digits.iter().fold(0u8, |acc, d| (acc << 3) | (d - b'0')));
+                idx += 4;
+            }
+            None => {
+                out.push(bytes[idx]);
+                idx += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn mount_for<'a>(mounts: &'a [Mount], path: &str) -> Option<&'a Mount> {
+    // max_by_key keeps the last entry, which shadows earlier ones at the same point.
+    mounts
+        .iter()
+        .filter(|mount| {
+            mount.point == "/"
+                || path == mount.point
+                || path.starts_with(&format!("{}/", mount.point))
+        })
+        .max_by_key(|mount| mount.point.len())
+}
+
+fn has_executable_magic(path: &Path) -> bool {
+    let mut magic = [0u8; 4];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok_and(|_| magic == *b"\x7fELF" || magic.starts_with(b"#!"))
+}
+
+fn reads_script_from_stdin(argv: &[String]) -> bool {
+    match argv {
+        [_] => true,
+        [_, rest @ ..] => rest
+            .iter()
+            .all(|arg| arg == "-" || arg == "-s" || arg == "-i" || arg == "-u"),
+        [] => false,
+    }
+}
+
+/// First operand an interpreter treats as a script path, if any.
+fn script_argument<'a>(argv: &'a [String], options: &InterpreterOptions) -> Option<&'a str> {
+    let mut args = argv.iter().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-" | "--" => return args.next().map(String::as_str),
+            flag if options.inline.contains(&flag) => return None,
+            flag if options.valued.contains(&flag) => {
+                args.next();
+            }
+            flag if flag.starts_with('-') || flag.starts_with('+') => continue,
+            script => return Some(script),
+        }
+    }
+    None
+}
+
+fn is_interpreter(name: &str) -> bool {
+    INTERPRETERS
+        .iter()
+        .any(|interp| matches_interpreter(name, interp))
+}
+
+/// busybox is left out: its first operand is the applet name.
+fn interpreter_options(name: &str) -> Option<&'static InterpreterOptions> {
+    INTERPRETER_OPTIONS.iter().find(|options| {
+        options
+            .names
+            .iter()
+            .any(|interp| matches_interpreter(name, interp))
+    })
+}
+
+/// Matches `python`, `python3` or `python3.12`, but not `pythonista`.
+fn matches_interpreter(name: &str, interp: &str) -> bool {
+    name.strip_prefix(interp).is_some_and(|rest| {
+        rest.is_empty() || rest.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+    })
+}
+
+fn basename(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
 
-    Ok(Some(format!(
-        "pid={}, comm={}, exe={}, cwd={}, cmdline={}",
-        pid, comm, exe, cwd, cmdline
-    )))
+fn carve(dir: &str, pid: i32, label: &str, source: &Path) -> Result<String, String> {
+    let file = fs::File::open(source).map_err(|err| err.to_string())?;
+    let mut data = Vec::new();
+    file.take(CARVE_LIMIT)
+        .read_to_end(&mut data)
+        .map_err(|err| err.to_string())?;
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+        .map_err(|err| err.to_string())?;
+    let dest = Path::new(dir).join(format!("pid{pid}_{label}.bin"));
+    // Payloads may carry stolen credentials; keep them private and never
+    // follow or overwrite a file planted at the destination.
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&dest)
+        .and_then(|mut out| out.write_all(&data))
+        .map_err(|err| format!("{}: {err}", dest.display()))?;
+    let digest = Sha256::digest(&data);
+    let hex: String = digest.iter().map(|b| format!("{b:02x}")).collect();
+    Ok(format!(
+        "carved_{label}={} ({}B sha256={hex})",
+        dest.display(),
+        data.len()
+    ))
 }
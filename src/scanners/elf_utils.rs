@@ -0,0 +1,260 @@
+use std::fs;
+
+pub const EM_X86_64: u16 = 62;
+pub const EM_AARCH64: u16 = 183;
+
+const MAX_IMAGE_SIZE: u64 = 256 << 20;
+const PT_LOAD: u32 = 1;
+const SHT_RELA: u32 = 4;
+const SHT_DYNAMIC: u32 = 6;
+const SHT_DYNSYM: u32 = 11;
+const DT_NEEDED: u64 = 1;
+const DT_SONAME: u64 = 14;
+
+#[derive(Debug, Clone)]
+pub struct LoadSegment {
+    pub vaddr: u64,
+    pub offset: u64,
+    pub filesz: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ElfSymbol {
+    pub name: String,
+    pub value: u64,
+    pub defined: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ElfRelocation {
+    pub offset: u64,
+    pub kind: u32,
+    pub symbol: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct ElfImage {
+    pub machine: u16,
+    pub soname: Option<String>,
+    pub needed: Vec<String>,
+    pub loads: Vec<LoadSegment>,
+    pub symbols: Vec<ElfSymbol>,
+    pub relocations: Vec<ElfRelocation>,
+    pub data: Vec<u8>,
+}
+
+impl ElfImage {
+    pub fn open(path: &str) -> Result<Self, String> {
+        let meta = fs::metadata(path).map_err(|err| format!("failed to stat {path}: {err}"))?;
+        if meta.len() > MAX_IMAGE_SIZE {
+            return Err(format!("{path} exceeds {MAX_IMAGE_SIZE} bytes"));
+        }
+        let data = fs::read(path).map_err(|err| format!("failed to read {path}: {err}"))?;
+        Self::parse(data).map_err(|err| format!("{path}: {err}"))
+    }
+
+    pub fn parse(data: Vec<u8>) -> Result<Self, String> {
+        if data.len() < 64 || !data.starts_with(b"\x7fELF") {
+            return Err("not an ELF image".to_string());
+        }
+        if data[4] != 2 || data[5] != 1 {
+            return Err("only 64-bit little-endian ELF is supported".to_string());
+        }
+
+        let machine = read_u16(&data, 18).unwrap_or(0);
+        let phoff = read_u64(&data, 32).unwrap_or(0) as usize;
+        let shoff = read_u64(&data, 40).unwrap_or(0) as usize;
+        let phentsize = read_u16(&data, 54).unwrap_or(0) as usize;
+        let phnum = read_u16(&data, 56).unwrap_or(0) as usize;
+        let shentsize = read_u16(&data, 58).unwrap_or(0) as usize;
+        let shnum = read_u16(&data, 60).unwrap_or(0) as usize;
+
+        let mut loads = Vec::new();
+        for idx in 0..phnum {
+            let Some(base) = table_entry(phoff, idx, phentsize) else {
+                break;
+            };
+            if read_u32(&data, base) != Some(PT_LOAD) {
+                continue;
+            }
+            loads.push(LoadSegment {
+                offset: read_u64(&data, base.saturating_add(8)).unwrap_or(0),
+                vaddr: read_u64(&data, base.saturating_add(16)).unwrap_or(0),
+                filesz: read_u64(&data, base.saturating_add(32)).unwrap_or(0),
+            });
+        }
+
+        let sections: Vec<Section> = (0..shnum)
+            .filter_map(|idx| Section::read(&data, table_entry(shoff, idx, shentsize)?))
+            .collect();
+
+        let mut image = ElfImage {
+            machine,
+            soname: None,
+            needed: Vec::new(),
+            loads,
+            symbols: Vec::new(),
+            relocations: Vec::new(),
+            data: Vec::new(),
+        };
+
+        let dynsym_idx = sections.iter().position(|s| s.kind == SHT_DYNSYM);
+        if let Some(idx) = dynsym_idx {
+            let symtab = &sections[idx];
+            let strtab = sections.get(symtab.link as usize);
+            for entry in 0..(symtab.size / 24) as usize {
+                let Some(base) = table_entry(symtab.offset as usize, entry, 24) else {
+                    break;
+                };
+                let (Some(name_off), Some(shndx), Some(value)) = (
+                    read_u32(&data, base),
+                    read_u16(&data, base.saturating_add(6)),
+                    read_u64(&data, base.saturating_add(8)),
+                ) else {
+                    break;
+                };
+                image.symbols.push(ElfSymbol {
+                    name: strtab
+                        .and_then(|s| s.offset.checked_add(name_off as u64))
+                        .and_then(|offset| read_cstr(&data, offset as usize))
+                        .unwrap_or_default(),
+                    value,
+                    defined: shndx != 0,
+                });
+            }
+        }
+
+        for section in &sections {
+            if section.kind == SHT_RELA && Some(section.link as usize) == dynsym_idx {
+                for entry in 0..(section.size / 24) as usize {
+                    let Some(base) = table_entry(section.offset as usize, entry, 24) else {
+                        break;
+                    };
+                    let (Some(offset), Some(info)) = (
+                        read_u64(&data, base),
+                        read_u64(&data, base.saturating_add(8)),
+                    ) else {
+                        break;
+                    };
+                    image.relocations.push(ElfRelocation {
+                        offset,
+                        kind: (info & 0xffff_ffff) as u32,
+                        symbol: (info >> 32) as usize,
+                    });
+                }
+            }
+            if section.kind == SHT_DYNAMIC {
+                let strtab = sections.get(section.link as usize);
+                for entry in 0..(section.size / 16) as usize {
+                    let Some(base) = table_entry(section.offset as usize, entry, 16) else {
+                        break;
+                    };
+                    let (Some(tag), Some(value)) = (
+                        read_u64(&data, base),
+                        read_u64(&data, base.saturating_add(8)),
+                    ) else {
+                        break;
+                    };
+                    if tag != DT_NEEDED && tag != DT_SONAME {
+                        continue;
+                    }
+                    let Some(name) = strtab
+                        .and_then(|s| s.offset.checked_add(value))
+                        .and_then(|offset| read_cstr(&data, offset as usize))
+                    else {
+                        continue;
+                    };
+                    if tag == DT_NEEDED {
+                        image.needed.push(name);
+                    } else {
+                        image.soname = Some(name);
+                    }
+                }
+            }
+        }
+
+        image.data = data;
+        Ok(image)
+    }
+
+    /// Virtual address of the first loadable segment, page aligned.
+    pub fn load_bias_vaddr(&self) -> u64 {
+        self.loads
+            .iter()
+            .map(|segment| segment.vaddr & !0xfff)
+            .min()
+            .unwrap_or(0)
+    }
+
+    pub fn vaddr_to_offset(&self, vaddr: u64) -> Option<u64> {
+        self.loads
+            .iter()
+            .find(|s| vaddr >= s.vaddr && vaddr - s.vaddr < s.filesz)
+            .and_then(|s| (vaddr - s.vaddr).checked_add(s.offset))
+    }
+
+    pub fn bytes_at_vaddr(&self, vaddr: u64, len: usize) -> Option<&[u8]> {
+        let offset = self.vaddr_to_offset(vaddr)? as usize;
+        self.data.get(offset..offset.checked_add(len)?)
+    }
+
+    pub fn defines(&self, name: &str) -> bool {
+        self.symbols
+            .iter()
+            .any(|sym| sym.defined && sym.value != 0 && sym.name == name)
+    }
+
+    /// Relocation types that fill GOT slots with a symbol address.
+    pub fn is_got_relocation(&self, kind: u32) -> bool {
+        match self.machine {
+            EM_X86_64 => kind == 6 || kind == 7,
+            EM_AARCH64 => kind == 1025 || kind == 1026,
+            _ => false,
+        }
+    }
+}
+
+struct Section {
+    kind: u32,
+    offset: u64,
+    size: u64,
+    link: u32,
+}
+
+impl Section {
+    fn read(data: &[u8], base: usize) -> Option<Self> {
+        Some(Section {
+            kind: read_u32(data, base.checked_add(4)?)?,
+            offset: read_u64(data, base.checked_add(24)?)?,
+            size: read_u64(data, base.checked_add(32)?)?,
+            link: read_u32(data, base.checked_add(40)?)?,
+        })
+    }
+}
+
+/// Offset of entry `idx` in a header table, or `None` when the header's
+/// values overflow.
+fn table_entry(start: usize, idx: usize, entsize: usize) -> Option<usize> {
+    start.checked_add(idx.checked_mul(entsize)?)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset.checked_add(8)?)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn read_cstr(data: &[u8], offset: usize) -> Option<String> {
+    let tail = data.get(offset..)?;
+    let end = tail.iter().position(|b| *b == 0)?;
+    Some(String::from_utf8_lossy(&tail[..end]).into_owned())
+}
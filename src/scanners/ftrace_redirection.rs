@@ -52,12 +52,12 @@ fn collect_sensitive_matches(content: &str) -> BTreeSet<String> {
         .collect()
 }
 
-fn is_sensitive_symbol(symbol: &str) -> bool {
+pub fn is_sensitive_symbol(symbol: &str) -> bool {
     const PREFIXES: [&str; 4] = ["sys_", "vfs_", "tcp_", "security_"];
     PREFIXES.iter().any(|prefix| symbol.starts_with(prefix))
 }
 
-fn find_tracefs_root() -> io::Result<PathBuf> {
+pub fn find_tracefs_root() -> io::Result<PathBuf> {
     const TRACEFS_ROOTS: [&str; 2] = ["/sys/kernel/tracing", "/sys/kernel/debug/tracing"];
     for root in TRACEFS_ROOTS {
         let path = Path::new(root);
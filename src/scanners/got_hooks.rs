@@ -0,0 +1,426 @@
+use super::{elf_utils::ElfImage, ownerless_sockets::collect_owners};
+use crate::ScanOutcome;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    fs, io,
+    os::unix::fs::FileExt,
+};
+
+const DAEMONS: &[&str] = &[
+    "sshd",
+    "systemd",
+    "cron",
+    "crond",
+    "atd",
+    "rsyslogd",
+    "systemd-journal",
+    "systemd-logind",
+    "dbus-daemon",
+    "auditd",
+    "login",
+    "sudo",
+    "nginx",
+    "apache2",
+    "httpd",
+];
+
+// Imports commonly hooked by userland rootkits (Jynx, Azazel, libprocesshider).
+const WATCHED_SYMBOLS: &[&str] = &[
+    "readdir",
+    "readdir64",
+    "opendir",
+    "open",
+    "open64",
+    "openat",
+    "fopen",
+    "fopen64",
+    "stat",
+    "lstat",
+    "__xstat",
+    "__lxstat",
+    "unlink",
+    "unlinkat",
+    "kill",
+    "accept",
+    "accept4",
+    "read",
+    "write",
+    "execve",
+    "getpwnam",
+    "crypt",
+    "pam_authenticate",
+    "pam_open_session",
+    "pam_acct_mgmt",
+];
+
+const PROLOGUE_LEN: usize = 16;
+
+struct LoadedObject {
+    path: String,
+    key: String,
+    base_mapping: (u64, u64),
+    ranges: Vec<(u64, u64)>,
+}
+
+pub fn run() -> ScanOutcome {
+    let mut findings = Vec::new();
+    let mut errors = Vec::new();
+
+    let targets = target_processes()?;
+
+    let mut cache: HashMap<String, Option<ElfImage>> = HashMap::new();
+    let mut denied = 0usize;
+
+    for (pid, reason) in &targets {
+        match inspect_process(*pid, reason, &mut cache) {
+            Ok(Some(mut list)) => findings.append(&mut list),
+            Ok(None) => denied += 1,
+            Err(err) => errors.push(err),
+        }
+    }
+
+    if denied > 0 {
+        errors.push(format!("process memory unreadable for {denied} targets"));
+    }
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        findings.dedup();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+fn target_processes() -> Result<BTreeMap<i32, String>, String> {
+    let mut targets = BTreeMap::new();
+    let self_pid = std::process::id() as i32;
+
+    let proc_dir = fs::read_dir("/proc").map_err(|err| format!("failed to read /proc: {err}"))?;
+    for entry in proc_dir.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<i32>().ok())
+        else {
+            continue;
+        };
+        if pid == self_pid {
+            continue;
+        }
+        let comm = command(pid);
+        if pid == 1 || DAEMONS.contains(&comm.as_str()) {
+            targets.insert(pid, "daemon".to_string());
+        }
+    }
+
+    let listeners = listening_inodes();
+    if !listeners.is_empty() {
+        let mut owners: BTreeMap<String, BTreeSet<i32>> = BTreeMap::new();
+        collect_owners("/proc", &mut owners)?;
+        for inode in listeners {
+            for pid in owners.get(&inode).into_iter().flatten() {
+                if *pid != self_pid {
+                    targets
+                        .entry(*pid)
+                        .or_insert_with(|| "listener".to_string());
+                }
+            }
+        }
+    }
+
+    Ok(targets)
+}
+
+fn listening_inodes() -> BTreeSet<String> {
+    let mut inodes = BTreeSet::new();
+    for (path, state) in [
+        ("/proc/net/tcp", "0A"),
+        ("/proc/net/tcp6", "0A"),
+        ("/proc/net/udp", "07"),
+        ("/proc/net/udp6", "07"),
+    ] {
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        for line in content.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() > 9 && fields[3] == state && fields[9] != "0" {
+                inodes.insert(fields[9].to_string());
+            }
+        }
+    }
+    inodes
+}
+
+fn inspect_process(
+    pid: i32,
+    reason: &str,
+    cache: &mut HashMap<String, Option<ElfImage>>,
+) -> Result<Option<Vec<String>>, String> {
+    let maps_path = format!("/proc/{pid}/maps");
+    let maps = match fs::read_to_string(&maps_path) {
+        Ok(maps) => maps,
+        Err(err) if err.kind() == io::ErrorKind::PermissionDenied => return Ok(None),
+        Err(err) => return Err(format!("{maps_path}: {err}")),
+    };
+    let objects = loaded_objects(&maps);
+    if objects.is_empty() {
+        return Ok(Some(Vec::new()));
+    }
+
+    let mem_path = format!("/proc/{pid}/mem");
+    let mem = match fs::File::open(&mem_path) {
+        Ok(mem) => mem,
+        Err(err) if err.kind() == io::ErrorKind::PermissionDenied => return Ok(None),
+        Err(err) => return Err(format!("{mem_path}: {err}")),
+    };
+
+    for object in &objects {
+        if cache.contains_key(&object.key) {
+            continue;
+        }
+        let (start, end) = object.base_mapping;
+        let image = ElfImage::open(&format!("/proc/{pid}/map_files/{start:x}-{end:x}"))
+            .or_else(|_| ElfImage::open(&format!("/proc/{pid}/root{}", object.path)))
+            .ok();
+        cache.insert(object.key.clone(), image);
+    }
+
+    let images: Vec<Option<&ElfImage>> = objects
+        .iter()
+        .map(|object| cache.get(&object.key).and_then(Option::as_ref))
+        .collect();
+    // A crafted ELF can claim a load address above where it is mapped;
+    // such objects get no base and are skipped.
+    let bases: Vec<Option<u64>> = objects
+        .iter()
+        .zip(&images)
+        .map(|(object, image)| {
+            object
+                .base_mapping
+                .0
+                .checked_sub(image.map_or(0, |image| image.load_bias_vaddr()))
+        })
+        .collect();
+
+    let exe = fs::read_link(format!("/proc/{pid}/exe"))
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let scope = lookup_scope(&exe, &objects, &images);
+
+    let comm = command(pid);
+    let mut findings = Vec::new();
+    let mut checked_prologues = HashSet::new();
+
+    for (importer_idx, image) in images.iter().enumerate() {
+        let (Some(image), Some(base)) = (image, bases[importer_idx]) else {
+            continue;
+        };
+        for reloc in &image.relocations {
+            if !image.is_got_relocation(reloc.kind) {
+                continue;
+            }
+            let Some(symbol) = image.symbols.get(reloc.symbol) else {
+                continue;
+            };
+            if symbol.defined || !WATCHED_SYMBOLS.contains(&symbol.name.as_str()) {
+                continue;
+            }
+
+            let Some(slot) = base.checked_add(reloc.offset) else {
+                continue;
+            };
+            let mut buf = [0u8; 8];
+            if mem.read_at(&mut buf, slot).ok() != Some(8) {
+                continue;
+            }
+            let target = u64::from_le_bytes(buf);
+            if target == 0 {
+                continue;
+            }
+
+            let expected = scope
+                .iter()
+                .copied()
+                .find(|idx| images[*idx].is_some_and(|image| image.defines(&symbol.name)));
+
+            match expected {
+                Some(provider) if checked_prologues.insert((provider, symbol.name.clone())) => {
+                    findings.extend(check_prologue(
+                        pid,
+                        &comm,
+                        &mem,
+                        &symbol.name,
+                        &objects[provider],
+                        images[provider],
+                        bases[provider],
+                    ));
+                }
+                _ => {}
+            }
+
+            let owner = objects.iter().position(|object| {
+                object
+                    .ranges
+                    .iter()
+                    .any(|(start, end)| target >= *start && target < *end)
+            });
+            // Lazily bound slots still point back into the importer's own PLT.
+            if owner == Some(importer_idx) {
+                continue;
+            }
+
+            let issue = match (owner, expected) {
+                (None, _) => "got_unmapped",
+                (Some(owner), Some(expected)) if owner != expected => "got_hijack",
+                (Some(owner), None) if !scope.contains(&owner) => "got_outside_scope",
+                _ => continue,
+            };
+
+            findings.push(format!(
+                "pid={}, comm={}, target={}, importer={}, symbol={}, got={:#x}, resolved={}, expected={}, issue={}",
+                pid,
+                comm,
+                reason,
+                objects[importer_idx].path,
+                symbol.name,
+                target,
+                owner.map_or("unmapped", |idx| objects[idx].path.as_str()),
+                expected.map_or("unknown", |idx| objects[idx].path.as_str()),
+                issue
+            ));
+        }
+    }
+
+    Ok(Some(findings))
+}
+
+fn check_prologue(
+    pid: i32,
+    comm: &str,
+    mem: &fs::File,
+    name: &str,
+    object: &LoadedObject,
+    image: Option<&ElfImage>,
+    base: Option<u64>,
+) -> Vec<String> {
+    let (Some(image), Some(base)) = (image, base) else {
+        return Vec::new();
+    };
+    let mut findings = Vec::new();
+    let mut seen = HashSet::new();
+    for symbol in &image.symbols {
+        if !symbol.defined || symbol.name != name || !seen.insert(symbol.value) {
+            continue;
+        }
+        let Some(disk) = image.bytes_at_vaddr(symbol.value, PROLOGUE_LEN) else {
+            continue;
+        };
+        let Some(addr) = base.checked_add(symbol.value) else {
+            continue;
+        };
+        let mut live = [0u8; PROLOGUE_LEN];
+        if mem.read_at(&mut live, addr).ok() != Some(PROLOGUE_LEN) {
+            continue;
+        }
+        if live.as_slice() != disk {
+            findings.push(format!(
+                "pid={}, comm={}, object={}, symbol={}, addr={:#x}, memory={}, disk={}, issue=inline_hook",
+                pid,
+                comm,
+                object.path,
+                name,
+                addr,
+                hex(&live),
+                hex(disk)
+            ));
+        }
+    }
+    findings
+}
+
+fn loaded_objects(maps: &str) -> Vec<LoadedObject> {
+    let mut objects: Vec<LoadedObject> = Vec::new();
+    for line in maps.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 6 || !parts[5].starts_with('/') || parts[4] == "0" {
+            continue;
+        }
+        let Some((start, end)) = mapping_bounds(parts[0]) else {
+            continue;
+        };
+        let path = parts[5..].join(" ");
+        let key = format!("{}:{}", parts[3], parts[4]);
+        let offset = u64::from_str_radix(parts[2], 16).unwrap_or(u64::MAX);
+
+        if let Some(object) = objects.iter_mut().find(|object| object.path == path) {
+            object.ranges.push((start, end));
+            continue;
+        }
+        if offset != 0 {
+            continue;
+        }
+        objects.push(LoadedObject {
+            path,
+            key,
+            base_mapping: (start, end),
+            ranges: vec![(start, end)],
+        });
+    }
+    objects
+}
+
+/// Breadth-first DT_NEEDED order from the main executable, which is the
+/// global lookup scope the dynamic linker would build without preloads.
+fn lookup_scope(exe: &str, objects: &[LoadedObject], images: &[Option<&ElfImage>]) -> Vec<usize> {
+    let Some(root) = objects.iter().position(|object| object.path == exe) else {
+        return Vec::new();
+    };
+    let mut order = Vec::new();
+    let mut queue = VecDeque::from([root]);
+    let mut visited = HashSet::from([root]);
+
+    while let Some(idx) = queue.pop_front() {
+        order.push(idx);
+        let Some(image) = images[idx] else {
+            continue;
+        };
+        for needed in &image.needed {
+            let found = objects.iter().enumerate().position(|(other, object)| {
+                let soname = images[other].and_then(|image| image.soname.as_deref());
+                soname == Some(needed.as_str()) || object.path.rsplit('/').next() == Some(needed)
+            });
+            match found {
+                Some(other) if visited.insert(other) => queue.push_back(other),
+                _ => {}
+            }
+        }
+    }
+    order
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn mapping_bounds(range: &str) -> Option<(u64, u64)> {
+    let mut parts = range.split('-');
+    let start = u64::from_str_radix(parts.next()?, 16).ok()?;
+    let end = u64::from_str_radix(parts.next()?, 16).ok()?;
+    Some((start, end))
+}
+
+fn command(pid: i32) -> String {
+    fs::read_to_string(format!("/proc/{pid}/comm"))
+        .unwrap_or_else(|_| "unknown".to_string())
+        .trim()
+        .to_string()
+}
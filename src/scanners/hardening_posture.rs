@@ -0,0 +1,294 @@
+use crate::ScanOutcome;
+use std::{env, fs, io, path::Path};
+
+const PROFILE_ENV: &str = "GHOSTSCAN_HARDENING_PROFILE";
+const SECURITYFS_DIR: &str = "/sys/kernel/security";
+const LOCKDOWN_PATH: &str = "/sys/kernel/security/lockdown";
+const LSM_PATH: &str = "/sys/kernel/security/lsm";
+const SELINUX_ENFORCE_PATH: &str = "/sys/fs/selinux/enforce";
+const IMA_MEASUREMENTS_COUNT_PATH: &str = "/sys/kernel/security/ima/runtime_measurements_count";
+const EFI_DIR: &str = "/sys/firmware/efi";
+const EFIVARS_DIR: &str = "/sys/firmware/efi/efivars";
+const EFI_GLOBAL_GUID: &str = "8be4df61-93ca-11d2-aa0d-00e098032b8c";
+const MAJOR_LSMS: &[&str] = &["selinux", "apparmor", "smack", "tomoyo"];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Profile {
+    Baseline,
+    Strict,
+}
+
+#[derive(Clone, Copy)]
+enum Expect {
+    Any,
+    AtLeast(i64),
+    Equals(i64),
+}
+
+impl Expect {
+    fn accepts(&self, value: i64) -> bool {
+        match *self {
+            Expect::Any => true,
+            Expect::AtLeast(min) => value >= min,
+            Expect::Equals(want) => value == want,
+        }
+    }
+}
+
+struct SysctlCheck {
+    name: &'static str,
+    baseline: Expect,
+    strict: Expect,
+}
+
+const SYSCTLS: &[SysctlCheck] = &[
+    SysctlCheck {
+        name: "kernel.kptr_restrict",
+        baseline: Expect::AtLeast(1),
+        strict: Expect::Equals(2),
+    },
+    SysctlCheck {
+        name: "kernel.unprivileged_bpf_disabled",
+        baseline: Expect::AtLeast(1),
+        strict: Expect::AtLeast(1),
+    },
+    SysctlCheck {
+        name: "kernel.modules_disabled",
+        baseline: Expect::Any,
+        strict: Expect::Equals(1),
+    },
+    SysctlCheck {
+        name: "kernel.kexec_load_disabled",
+        baseline: Expect::Any,
+        strict: Expect::Equals(1),
+    },
+    SysctlCheck {
+        name: "kernel.yama.ptrace_scope",
+        baseline: Expect::AtLeast(1),
+        strict: Expect::AtLeast(2),
+    },
+    SysctlCheck {
+        name: "net.core.bpf_jit_harden",
+        baseline: Expect::Any,
+        strict: Expect::Equals(2),
+    },
+    SysctlCheck {
+        name: "kernel.perf_event_paranoid",
+        baseline: Expect::AtLeast(2),
+        strict: Expect::AtLeast(3),
+    },
+    SysctlCheck {
+        name: "fs.protected_symlinks",
+        baseline: Expect::Equals(1),
+        strict: Expect::Equals(1),
+    },
+    SysctlCheck {
+        name: "fs.protected_hardlinks",
+        baseline: Expect::Equals(1),
+        strict: Expect::Equals(1),
+    },
+    SysctlCheck {
+        name: "fs.protected_fifos",
+        baseline: Expect::AtLeast(1),
+        strict: Expect::Equals(2),
+    },
+    SysctlCheck {
+        name: "fs.protected_regular",
+        baseline: Expect::AtLeast(1),
+        strict: Expect::Equals(2),
+    },
+    SysctlCheck {
+        name: "vm.mmap_min_addr",
+        baseline: Expect::AtLeast(4096),
+        strict: Expect::AtLeast(65536),
+    },
+];
+
+pub fn run() -> ScanOutcome {
+    let profile = match env::var(PROFILE_ENV) {
+        Ok(value) => match value.trim() {
+            "" | "baseline" => Profile::Baseline,
+            "strict" => Profile::Strict,
+            other => {
+                return Err(format!(
+                    "unknown {PROFILE_ENV}={other} (expected baseline or strict)"
+                ));
+            }
+        },
+        Err(_) => Profile::Baseline,
+    };
+    let label = match profile {
+        Profile::Baseline => "baseline",
+        Profile::Strict => "strict",
+    };
+
+    let mut deviations = Vec::new();
+    let mut errors = Vec::new();
+
+    for check in SYSCTLS {
+        let expect = match profile {
+            Profile::Baseline => check.baseline,
+            Profile::Strict => check.strict,
+        };
+        let bound = match expect {
+            Expect::Any => continue,
+            Expect::AtLeast(min) => format!(">={min}"),
+            Expect::Equals(want) => format!("={want}"),
+        };
+
+        let path = format!("/proc/sys/{}", check.name.replace('.', "/"));
+        match fs::read_to_string(&path) {
+            Ok(raw) => match raw.trim().parse::<i64>() {
+                Ok(value) if expect.accepts(value) => {}
+                Ok(value) => deviations.push(format!(
+                    "setting={}, value={}, expected{}",
+                    check.name, value, bound
+                )),
+                Err(_) => errors.push(format!("{}: unparsable value {}", check.name, raw.trim())),
+            },
+            Err(err) if err.kind() == io::ErrorKind::NotFound => deviations.push(format!(
+                "setting={}, value=absent, expected{}",
+                check.name, bound
+            )),
+            Err(err) => errors.push(format!("{}: {err}", check.name)),
+        }
+    }
+
+    let secure_boot = match read_efi_flag("SecureBoot") {
+        Ok(state) => state,
+        Err(err) => {
+            errors.push(format!("SecureBoot: {err}"));
+            None
+        }
+    };
+    let setup_mode = read_efi_flag("SetupMode").unwrap_or(None);
+
+    // An empty /sys/kernel/security means securityfs is not mounted, not that
+    // the kernel lacks lockdown, LSMs or IMA.
+    let securityfs = Path::new(SECURITYFS_DIR)
+        .read_dir()
+        .is_ok_and(|mut entries| entries.next().is_some());
+    if !securityfs {
+        errors.push(format!("securityfs not mounted at {SECURITYFS_DIR}"));
+    }
+
+    let lockdown = match fs::read_to_string(LOCKDOWN_PATH) {
+        Ok(content) => selected_mode(&content),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => {
+            errors.push(format!("{LOCKDOWN_PATH}: {err}"));
+            None
+        }
+    };
+    match (lockdown.as_deref(), secure_boot, profile) {
+        _ if !securityfs => {}
+        (Some("none") | None, Some(true), _) => deviations.push(format!(
+            "setting=lockdown, value={}, expected=integrity (secure_boot=enabled)",
+            lockdown.as_deref().unwrap_or("absent")
+        )),
+        (Some("none") | None, _, Profile::Strict) => deviations.push(format!(
+            "setting=lockdown, value={}, expected=integrity",
+            lockdown.as_deref().unwrap_or("absent")
+        )),
+        _ => {}
+    }
+
+    match fs::read_to_string(LSM_PATH) {
+        _ if !securityfs => {}
+        Ok(content) => {
+            let active: Vec<&str> = content.trim().split(',').collect();
+            if !active.iter().any(|lsm| MAJOR_LSMS.contains(lsm)) {
+                deviations.push(format!(
+                    "setting=lsm, value={}, expected=one_of({})",
+                    content.trim(),
+                    MAJOR_LSMS.join("|")
+                ));
+            }
+            if profile == Profile::Strict {
+                for required in ["yama", "lockdown"] {
+                    if !active.contains(&required) {
+                        deviations.push(format!(
+                            "setting=lsm, value={}, expected_includes={}",
+                            content.trim(),
+                            required
+                        ));
+                    }
+                }
+            }
+            if active.contains(&"selinux") {
+                match fs::read_to_string(SELINUX_ENFORCE_PATH) {
+                    Ok(mode) if mode.trim() == "0" => deviations
+                        .push("setting=selinux, value=permissive, expected=enforcing".to_string()),
+                    _ => {}
+                }
+            }
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            deviations.push("setting=lsm, value=absent, expected=active_lsm".to_string())
+        }
+        Err(err) => errors.push(format!("{LSM_PATH}: {err}")),
+    }
+
+    // boot_aggregate is always measured; anything beyond it needs a policy.
+    if profile == Profile::Strict && securityfs {
+        match fs::read_to_string(IMA_MEASUREMENTS_COUNT_PATH) {
+            Ok(count) if count.trim().parse::<u64>().unwrap_or(0) > 1 => {}
+            Ok(count) => deviations.push(format!(
+                "setting=ima_policy, value=measurements:{}, expected=policy_loaded",
+                count.trim()
+            )),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => deviations
+                .push("setting=ima_policy, value=absent, expected=policy_loaded".to_string()),
+            Err(err) => errors.push(format!("{IMA_MEASUREMENTS_COUNT_PATH}: {err}")),
+        }
+    }
+
+    match (secure_boot, setup_mode, profile) {
+        (_, Some(true), _) => deviations.push(
+            "setting=secure_boot, value=setup_mode, expected=user_mode (keys enrolled)".to_string(),
+        ),
+        (Some(false), _, Profile::Strict) => {
+            deviations.push("setting=secure_boot, value=disabled, expected=enabled".to_string())
+        }
+        (None, _, Profile::Strict) if !Path::new(EFI_DIR).exists() => {
+            deviations.push("setting=secure_boot, value=legacy_boot, expected=enabled".to_string())
+        }
+        _ => {}
+    }
+
+    if deviations.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        let mut findings: Vec<String> = deviations
+            .into_iter()
+            .map(|deviation| format!("profile={label}, {deviation}"))
+            .collect();
+        findings.sort();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+/// Picks the bracketed entry from files like `none [integrity] confidentiality`.
+fn selected_mode(content: &str) -> Option<String> {
+    content
+        .split_whitespace()
+        .find_map(|token| token.strip_prefix('[')?.strip_suffix(']'))
+        .map(str::to_string)
+}
+
+/// Reads a boolean EFI global variable; the first four bytes are attributes.
+fn read_efi_flag(name: &str) -> io::Result<Option<bool>> {
+    let path = format!("{EFIVARS_DIR}/{name}-{EFI_GLOBAL_GUID}");
+    match fs::read(&path) {
+        Ok(data) => Ok(data.get(4).map(|value| *value == 1)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
@@ -1,3 +1,4 @@
+use super::netns_utils::sweep_net_namespaces;
 use crate::ScanOutcome;
 use std::{
     collections::BTreeMap,
@@ -318,9 +319,11 @@ fn collect_bpf_listeners(entry_limit: usize) -> Result<BpfListenerSnapshot, Stri
     bpf_support::collect_listeners(entry_limit)
 }
 
+/// Reads the calling thread's namespace, which differs from the process's
+/// while a namespace sweep has it inside a foreign netns.
 fn current_netns_inum() -> Result<u32, String> {
-    let link = fs::read_link("/proc/self/ns/net")
-        .map_err(|err| format!("failed to read /proc/self/ns/net: {err}"))?;
+    let link = fs::read_link("/proc/thread-self/ns/net")
+        .map_err(|err| format!("failed to read /proc/thread-self/ns/net: {err}"))?;
     let target = link.to_string_lossy();
     let start = target
         .find('[')
@@ -350,6 +353,24 @@ pub fn run() -> ScanOutcome {
         return Err("ss not available to query listeners".to_string());
     }
 
+    let (mut findings, errors) = sweep_net_namespaces(scan_namespace)?;
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+fn scan_namespace(proc_net: &str) -> (Vec<String>, Vec<String>) {
     let mut errors = Vec::new();
 
     let bpf_snapshot = match collect_bpf_listeners(BPF_LISTENER_ENTRY_LIMIT) {
@@ -371,7 +392,7 @@ pub fn run() -> ScanOutcome {
             Err(err) => errors.push(err),
         }
 
-        match collect_proc(proto) {
+        match collect_proc(proc_net, proto) {
             Ok(map) => proc.extend(map),
             Err(err) => errors.push(err),
         }
@@ -449,19 +470,7 @@ pub fn run() -> ScanOutcome {
         findings.push(segments.join(", "));
     }
 
-    if findings.is_empty() {
-        if errors.is_empty() {
-            Ok(None)
-        } else {
-            Err(errors.join(", "))
-        }
-    } else {
-        findings.sort();
-        if !errors.is_empty() {
-            findings.push(format!("collection_errors={}", errors.join(", ")));
-        }
-        Ok(Some(findings.join("\n")))
-    }
+    (findings, errors)
 }
 
 fn collect_ss(flags: &[&str], proto: &str) -> Result<BTreeMap<SocketKey, NetlinkEntry>, String> {
@@ -569,11 +578,11 @@ fn normalize_endpoint(raw: &str) -> Option<String> {
     None
 }
 
-fn collect_proc(proto: &str) -> Result<BTreeMap<SocketKey, ()>, String> {
+fn collect_proc(proc_net: &str, proto: &str) -> Result<BTreeMap<SocketKey, ()>, String> {
     let mut map = BTreeMap::new();
     let files = [
-        format!("/proc/net/{proto}"),
-        format!("/proc/net/{}6", proto),
+        format!("{proc_net}/{proto}"),
+        format!("{proc_net}/{}6", proto),
     ];
 
     for path in files {
@@ -615,18 +624,21 @@ fn collect_proc(proto: &str) -> Result<BTreeMap<SocketKey, ()>, String> {
     Ok(map)
 }
 
-fn parse_proc_endpoint(raw: &str, ipv6: bool) -> Option<String> {
+pub fn parse_proc_endpoint(raw: &str, ipv6: bool) -> Option<String> {
+    let (ip, port) = parse_proc_address(raw, ipv6)?;
+    Some(format_endpoint(ip, port))
+}
+
+pub fn parse_proc_address(raw: &str, ipv6: bool) -> Option<(IpAddr, u16)> {
     let mut parts = raw.split(':');
     let addr_hex = parts.next()?;
     let port_hex = parts.next()?;
     let port = u16::from_str_radix(port_hex, 16).ok()?;
 
     if ipv6 {
-        let ip = parse_ipv6(addr_hex)?;
-        Some(format_endpoint(IpAddr::V6(ip), port))
+        Some((IpAddr::V6(parse_ipv6(addr_hex)?), port))
     } else {
-        let ip = parse_ipv4(addr_hex)?;
-        Some(format_endpoint(IpAddr::V4(ip), port))
+        Some((IpAddr::V4(parse_ipv4(addr_hex)?), port))
     }
 }
 
@@ -658,7 +670,7 @@ fn parse_ipv6(hex: &str) -> Option<Ipv6Addr> {
     Some(Ipv6Addr::from(bytes))
 }
 
-fn format_endpoint(addr: IpAddr, port: u16) -> String {
+pub fn format_endpoint(addr: IpAddr, port: u16) -> String {
     match addr {
         IpAddr::V4(v4) => format!("{}:{}", v4, port),
         IpAddr::V6(v6) => format!("[{}]:{}", v6, port),
@@ -74,7 +74,7 @@ pub fn run() -> ScanOutcome {
     }
 }
 
-fn collect_proc_modules() -> io::Result<HashSet<String>> {
+pub fn collect_proc_modules() -> io::Result<HashSet<String>> {
     let content = fs::read_to_string("/proc/modules")?;
     let mut modules = HashSet::new();
 
@@ -89,7 +89,7 @@ fn collect_proc_modules() -> io::Result<HashSet<String>> {
     Ok(modules)
 }
 
-fn collect_sysfs_modules() -> io::Result<HashSet<String>> {
+pub fn collect_sysfs_modules() -> io::Result<HashSet<String>> {
     let mut modules = HashSet::new();
     for entry in fs::read_dir("/sys/module")? {
         let entry = entry?;
@@ -105,7 +105,7 @@ fn collect_sysfs_modules() -> io::Result<HashSet<String>> {
     Ok(modules)
 }
 
-fn collect_kallsyms_modules() -> io::Result<HashSet<String>> {
+pub fn collect_kallsyms_modules() -> io::Result<HashSet<String>> {
     let file = File::open("/proc/kallsyms")?;
     let reader = BufReader::new(file);
     let mut modules = HashSet::new();
@@ -0,0 +1,464 @@
+use crate::ScanOutcome;
+use flate2::read::GzDecoder;
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    io::{self, Cursor, Read},
+    path::Path,
+};
+
+const CPIO_NEWC_MAGIC: &[u8] = b"070701";
+const CPIO_CRC_MAGIC: &[u8] = b"070702";
+const CPIO_HEADER_LEN: usize = 110;
+const CPIO_TRAILER: &str = "TRAILER!!!";
+const MAX_ENTRY_SIZE: u64 = 64 * 1024 * 1024;
+const MAX_NAME_SIZE: usize = 4096;
+
+const EARLY_CPIO_PREFIXES: &[&str] = &["kernel/", "lib/firmware/", "usr/lib/firmware/"];
+
+const MACHINE_ID_PATH: &str = "/etc/machine-id";
+const DRACUT_MODULE_DIRS: &[&str] = &["/usr/lib/dracut/modules.d", "/lib/dracut/modules.d"];
+
+const PERSISTENCE_PATHS: &[&str] = &[
+    "etc/ld.so.preload",
+    "etc/ld.so.conf",
+    "etc/cron",
+    "var/spool/cron",
+    "etc/systemd/system",
+    "usr/lib/systemd/system",
+    "lib/systemd/system",
+    "etc/rc.local",
+    "etc/init.d",
+    "etc/profile",
+    "etc/bash.bashrc",
+    "etc/pam.d",
+    "etc/passwd",
+    "etc/shadow",
+    "etc/sudoers",
+    "etc/ssh",
+    "root/.ssh",
+];
+const ROOT_MOUNT_PREFIXES: &[&str] = &["/sysroot/", "${rootmnt}/", "$rootmnt/", "${rootmnt%/}/"];
+
+struct CpioEntry {
+    name: String,
+    mode: u32,
+    data: Option<Vec<u8>>,
+}
+
+struct CpioReader<R> {
+    inner: R,
+    offset: u64,
+}
+
+impl<R: Read> CpioReader<R> {
+    fn new(inner: R) -> Self {
+        Self { inner, offset: 0 }
+    }
+
+    /// The kernel tolerates zero padding between archives, so leading NULs
+    /// are skipped.
+    fn next_entry(&mut self) -> Result<Option<CpioEntry>, String> {
+        let mut header = [0u8; CPIO_HEADER_LEN];
+        loop {
+            let mut byte = [0u8; 1];
+            match self.inner.read(&mut byte) {
+                Ok(0) => return Ok(None),
+                Ok(_) => {
+                    self.offset += 1;
+                    if byte[0] != 0 {
+                        header[0] = byte[0];
+                        break;
+                    }
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(format!("read failed: {err}")),
+            }
+        }
+        self.read_exact(&mut header[1..])?;
+
+        if &header[..6] != CPIO_NEWC_MAGIC && &header[..6] != CPIO_CRC_MAGIC {
+            return Err(format!(
+                "bad cpio magic at offset {}",
+                self.offset - CPIO_HEADER_LEN as u64
+            ));
+        }
+        let field = |idx: usize| -> Result<u64, String> {
+            let raw = &header[6 + idx * 8..14 + idx * 8];
+            let text = std::str::from_utf8(raw).map_err(|_| "non-ascii cpio header")?;
+            u64::from_str_radix(text, 16).map_err(|_| format!("bad cpio field {text}"))
+        };
+        let mode = field(1)? as u32;
+        let file_size = field(6)?;
+        let name_size = field(11)? as usize;
+        if name_size > MAX_NAME_SIZE {
+            return Err(format!(
+                "cpio name size {} exceeds {} at offset {}",
+                name_size,
+                MAX_NAME_SIZE,
+                self.offset - CPIO_HEADER_LEN as u64
+            ));
+        }
+
+        let mut name = vec![0u8; name_size];
+        self.read_exact(&mut name)?;
+        self.skip_padding()?;
+        let name = String::from_utf8_lossy(name.strip_suffix(&[0]).unwrap_or(&name)).into_owned();
+
+        let data = if file_size <= MAX_ENTRY_SIZE {
+            let mut data = vec![0u8; file_size as usize];
+            self.read_exact(&mut data)?;
+            Some(data)
+        } else {
+            let skipped = io::copy(&mut (&mut self.inner).take(file_size), &mut io::sink())
+                .map_err(|err| format!("read failed: {err}"))?;
+            self.offset += skipped;
+            None
+        };
+        self.skip_padding()?;
+
+        Ok(Some(CpioEntry { name, mode, data }))
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), String> {
+        self.inner
+            .read_exact(buf)
+            .map_err(|err| format!("truncated cpio archive at offset {}: {err}", self.offset))?;
+        self.offset += buf.len() as u64;
+        Ok(())
+    }
+
+    fn skip_padding(&mut self) -> Result<(), String> {
+        let pad = (4 - (self.offset % 4) as usize) % 4;
+        let mut buf = [0u8; 4];
+        self.read_exact(&mut buf[..pad])
+    }
+}
+
+#[derive(Default)]
+struct ImageReport {
+    segments: Vec<String>,
+    files: usize,
+    hooks: usize,
+    binaries: usize,
+    findings: Vec<String>,
+}
+
+pub fn run() -> ScanOutcome {
+    let release = fs::read_to_string("/proc/sys/kernel/osrelease")
+        .map_err(|err| format!("failed to read /proc/sys/kernel/osrelease: {err}"))?;
+    let release = release.trim();
+
+    let images = candidate_images(release);
+    if images.is_empty() {
+        return Ok(None);
+    }
+
+    let mut findings = Vec::new();
+    let mut errors = Vec::new();
+
+    for image in &images {
+        let mut report = ImageReport::default();
+        if let Err(err) = inspect_image(image, &mut report, &mut errors) {
+            errors.push(format!("{image}: {err}"));
+        }
+        if !report.findings.is_empty() {
+            findings.push(format!(
+                "image={}, segments={}, files={}, hooks={}, binaries={}",
+                image,
+                report.segments.join("|"),
+                report.files,
+                report.hooks,
+                report.binaries
+            ));
+            findings.extend(
+                report
+                    .findings
+                    .into_iter()
+                    .map(|finding| format!("image={image}, {finding}")),
+            );
+        }
+    }
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+fn candidate_images(release: &str) -> Vec<String> {
+    let mut candidates = vec![
+        format!("/boot/initrd.img-{release}"),
+        format!("/boot/initramfs-{release}.img"),
+        format!("/boot/initrd-{release}"),
+    ];
+    if is_arch_linux_release(release) {
+        candidates.push("/boot/initramfs-linux.img".to_string());
+    }
+    if let Ok(machine_id) = fs::read_to_string(MACHINE_ID_PATH) {
+        let machine_id = machine_id.trim();
+        for root in ["/boot", "/efi", "/boot/efi"] {
+            candidates.push(format!("{root}/{machine_id}/{release}/initrd"));
+        }
+    }
+
+    let mut images: Vec<String> = Vec::new();
+    for candidate in candidates {
+        let resolved = fs::canonicalize(&candidate).unwrap_or_else(|_| candidate.clone().into());
+        let resolved = resolved.to_string_lossy().into_owned();
+        if Path::new(&resolved).is_file() && !images.contains(&resolved) {
+            images.push(resolved);
+        }
+    }
+    images
+}
+
+// mkinitcpio names the image after the package, and only the `linux` package
+// has a plain `<version>-archN-M` release.
+fn is_arch_linux_release(release: &str) -> bool {
+    matches!(
+        release.split('-').collect::<Vec<_>>().as_slice(),
+        [_, arch, pkgrel]
+            if arch.starts_with("arch") && pkgrel.chars().all(|c| c.is_ascii_digit())
+    )
+}
+
+fn inspect_image(
+    image: &str,
+    report: &mut ImageReport,
+    errors: &mut Vec<String>,
+) -> Result<(), String> {
+    let data = fs::read(image).map_err(|err| format!("failed to read: {err}"))?;
+    let mut offset = 0usize;
+
+    while offset < data.len() {
+        if data[offset] == 0 {
+            offset += 1;
+            continue;
+        }
+        let rest = &data[offset..];
+        let index = report.segments.len();
+
+        if rest.starts_with(CPIO_NEWC_MAGIC) || rest.starts_with(CPIO_CRC_MAGIC) {
+            report.segments.push("cpio".to_string());
+            let mut reader = CpioReader::new(Cursor::new(rest));
+            while let Some(entry) = reader.next_entry()? {
+                if entry.name == CPIO_TRAILER {
+                    break;
+                }
+            }
+            let end = reader.offset as usize;
+            // A lone uncompressed archive is the main initramfs, not an early one.
+            let early = rest[end..].iter().any(|&byte| byte != 0);
+            let mut reader = CpioReader::new(Cursor::new(&rest[..end]));
+            while let Some(entry) = reader.next_entry()? {
+                if entry.name != CPIO_TRAILER {
+                    inspect_entry(&entry, index, early, report);
+                }
+            }
+            offset += end;
+            continue;
+        }
+
+        match compression_name(rest) {
+            Some("gzip") => {
+                report.segments.push("gzip".to_string());
+                let mut reader = CpioReader::new(GzDecoder::new(rest));
+                while let Some(entry) = reader.next_entry()? {
+                    if entry.name != CPIO_TRAILER {
+                        inspect_entry(&entry, index, false, report);
+                    }
+                }
+            }
+            Some(kind) => {
+                report.segments.push(kind.to_string());
+                errors.push(format!(
+                    "{image}: segment {index} is {kind} compressed and was not inspected"
+                ));
+            }
+            None => {
+                report.segments.push("unknown".to_string());
+                errors.push(format!(
+                    "{image}: unrecognised data at offset {offset}, stopped parsing"
+                ));
+            }
+        }
+        break;
+    }
+
+    Ok(())
+}
+
+fn compression_name(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(&[0x1f, 0x8b]) {
+        Some("gzip")
+    } else if data.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        Some("zstd")
+    } else if data.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+        Some("xz")
+    } else if data.starts_with(&[0x02, 0x21, 0x4c, 0x18])
+        || data.starts_with(&[0x04, 0x22, 0x4d, 0x18])
+    {
+        Some("lz4")
+    } else if data.starts_with(b"BZh") {
+        Some("bzip2")
+    } else if data.starts_with(&[0x5d, 0x00, 0x00]) {
+        Some("lzma")
+    } else if data.starts_with(&[0x89, b'L', b'Z', b'O']) {
+        Some("lzo")
+    } else {
+        None
+    }
+}
+
+fn inspect_entry(entry: &CpioEntry, segment: usize, early: bool, report: &mut ImageReport) {
+    const S_IFMT: u32 = 0o170000;
+    const S_IFREG: u32 = 0o100000;
+    if entry.mode & S_IFMT != S_IFREG {
+        return;
+    }
+    report.files += 1;
+
+    let name = entry.name.trim_start_matches("./").trim_start_matches('/');
+    let Some(data) = entry.data.as_deref() else {
+        return;
+    };
+    let mut issues = Vec::new();
+
+    // dracut marks its early archive with an `early_cpio` file.
+    if early
+        && name != "early_cpio"
+        && !EARLY_CPIO_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+    {
+        issues.push("unexpected_in_early_cpio".to_string());
+    }
+
+    let digest = hex_sha256(data);
+    let is_elf = data.starts_with(b"\x7fELF");
+
+    let hook = hook_sources(name);
+    if let Some(sources) = &hook {
+        report.hooks += 1;
+        if sources.is_empty() {
+            issues.push("hook_not_from_generator".to_string());
+        } else if !sources
+            .iter()
+            .any(|source| fs::read(source).is_ok_and(|host| hex_sha256(&host) == digest))
+        {
+            issues.push("hook_modified".to_string());
+        }
+    }
+
+    if is_elf {
+        report.binaries += 1;
+        if let Some(issue) = compare_with_host(name, data, &digest) {
+            issues.push(issue.to_string());
+        }
+    } else if data.starts_with(b"#!") || hook.is_some() {
+        let text = String::from_utf8_lossy(data);
+        for target in PERSISTENCE_PATHS {
+            if ROOT_MOUNT_PREFIXES
+                .iter()
+                .any(|prefix| text.contains(&format!("{prefix}{target}")))
+            {
+                issues.push(format!("touches_root_persistence({target})"));
+            }
+        }
+    }
+
+    if !issues.is_empty() {
+        report.findings.push(format!(
+            "segment={}, file=/{}, size={}, sha256={}, issues={}",
+            segment,
+            name,
+            data.len(),
+            digest,
+            issues.join("|")
+        ));
+    }
+}
+
+/// Returns `None` for files outside hook directories.
+fn hook_sources(name: &str) -> Option<Vec<String>> {
+    let file_name = name.rsplit('/').next().unwrap_or(name);
+
+    if let Some(rest) = name.strip_prefix("scripts/") {
+        if file_name == "ORDER" {
+            return None;
+        }
+        return Some(existing(&[
+            format!("/usr/share/initramfs-tools/scripts/{rest}"),
+            format!("/etc/initramfs-tools/scripts/{rest}"),
+        ]));
+    }
+    if let Some(rest) = name.strip_prefix("hooks/") {
+        return Some(existing(&[
+            format!("/usr/lib/initcpio/hooks/{rest}"),
+            format!("/etc/initcpio/hooks/{rest}"),
+        ]));
+    }
+    if name.starts_with("var/lib/dracut/hooks/") || name.starts_with("usr/lib/dracut/hooks/") {
+        // inst_hook installs "$moddir/foo.sh" as "<hook>/<prio>-foo.sh".
+        let source_name = file_name
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .trim_start_matches('-');
+        let mut sources = Vec::new();
+        for dir in DRACUT_MODULE_DIRS {
+            if let Ok(modules) = fs::read_dir(dir) {
+                for module in modules.flatten() {
+                    let candidate = module.path().join(source_name);
+                    if candidate.is_file() {
+                        sources.push(candidate.to_string_lossy().into_owned());
+                    }
+                }
+            }
+        }
+        return Some(sources);
+    }
+    None
+}
+
+fn compare_with_host(name: &str, data: &[u8], digest: &str) -> Option<&'static str> {
+    let candidates = [format!("/{name}"), format!("/usr/{name}")];
+    let Some(host) = candidates.iter().find_map(|path| fs::read(path).ok()) else {
+        // Modules may be stored compressed on the host and expanded in the image.
+        let compressed = name.ends_with(".ko")
+            && [".zst", ".xz", ".gz"].iter().any(|ext| {
+                candidates
+                    .iter()
+                    .any(|path| Path::new(&format!("{path}{ext}")).exists())
+            });
+        return (!compressed).then_some("binary_not_on_host");
+    };
+    // dracut strips binaries by default, which only ever shrinks them.
+    if hex_sha256(&host) != digest && data.len() >= host.len() {
+        return Some("differs_from_host");
+    }
+    None
+}
+
+fn existing(paths: &[String]) -> Vec<String> {
+    paths
+        .iter()
+        .filter(|path| Path::new(path).is_file())
+        .cloned()
+        .collect()
+}
+
+fn hex_sha256(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
@@ -1,38 +1,508 @@
+use super::modprobe_hotplug::investigate_target;
 use crate::ScanOutcome;
-use std::fs;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
+
+const GRUB_DEFAULTS: &str = "/etc/default/grub";
+const KERNEL_CMDLINE_FILE: &str = "/etc/kernel/cmdline";
+const GRUB_CONFIGS: &[&str] = &[
+    "/boot/grub/grub.cfg",
+    "/boot/grub2/grub.cfg",
+    "/boot/efi/EFI/grub.cfg",
+];
+const GRUBENV_FILES: &[&str] = &["/boot/grub/grubenv", "/boot/grub2/grubenv"];
+const EFI_VENDOR_DIR: &str = "/boot/efi/EFI";
+const LOADER_ENTRY_DIRS: &[&str] = &[
+    "/boot/loader/entries",
+    "/efi/loader/entries",
+    "/boot/efi/loader/entries",
+];
+
+// Parameters the bootloader or EFI stub appends on its own.
+const LOADER_ADDED_PARAMS: &[&str] = &["BOOT_IMAGE", "initrd"];
+
+const LOCAL_HOOK_DIRS: &[&str] = &[
+    "/etc/initramfs-tools/hooks",
+    "/etc/initramfs-tools/scripts/init-top",
+    "/etc/initramfs-tools/scripts/init-premount",
+    "/etc/initramfs-tools/scripts/init-bottom",
+    "/etc/initramfs-tools/scripts/local-top",
+    "/etc/initramfs-tools/scripts/local-premount",
+    "/etc/initramfs-tools/scripts/local-bottom",
+    "/etc/initramfs-tools/scripts/nfs-top",
+    "/etc/initramfs-tools/scripts/nfs-premount",
+    "/etc/initramfs-tools/scripts/nfs-bottom",
+    "/etc/initcpio/hooks",
+    "/etc/initcpio/install",
+];
+const DRACUT_CONF: &str = "/etc/dracut.conf";
+const DRACUT_CONF_DIR: &str = "/etc/dracut.conf.d";
+const MKINITCPIO_CONF: &str = "/etc/mkinitcpio.conf";
+
+struct BootEntry {
+    kernel: Option<String>,
+    default: bool,
+    params: BTreeSet<String>,
+}
 
 pub fn run() -> ScanOutcome {
     let content = fs::read_to_string("/proc/cmdline")
         .map_err(|err| format!("failed to read /proc/cmdline: {err}"))?;
 
     let mut findings = Vec::new();
+    let mut errors = Vec::new();
+
+    let live = tokenize(content.trim());
+    let entries = collect_boot_entries(&mut errors);
+    let boot_image = live
+        .iter()
+        .find_map(|param| param.strip_prefix("BOOT_IMAGE="));
+    let persisted = reference_params(&entries, boot_image);
+
+    for param in &live {
+        let mut issues = Vec::new();
+        if let Some(issue) = classify_param(param) {
+            issues.push(issue);
+        }
+        if !entries.is_empty()
+            && !LOADER_ADDED_PARAMS.contains(&param_key(param).as_str())
+            && !persisted.contains(param)
+        {
+            issues.push("not_in_boot_config");
+        }
+        if !issues.is_empty() {
+            findings.push(format!("flag={}, issues={}", param, issues.join("|")));
+        }
+    }
+
+    inspect_initramfs_hooks(&mut findings, &mut errors);
+    inspect_initramfs_items(&mut findings, &mut errors);
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        findings.dedup();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+/// Splits a command line the way the kernel's `next_arg` does: whitespace
+/// separates parameters unless quoted, quotes are dropped, and everything
+/// after a bare `--` belongs to init.
+fn tokenize(cmdline: &str) -> Vec<String> {
+    let mut params = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut pending = false;
+
+    for c in cmdline.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                pending = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if pending {
+                    if current == "--" {
+                        return params;
+                    }
+                    params.push(std::mem::take(&mut current));
+                    pending = false;
+                }
+            }
+            c => {
+                current.push(c);
+                pending = true;
+            }
+        }
+    }
+    if pending && current != "--" {
+        params.push(current);
+    }
+    params
+}
+
+/// The kernel treats `-` and `_` in parameter names as equivalent.
+fn param_key(param: &str) -> String {
+    param.split('=').next().unwrap_or(param).replace('-', "_")
+}
+
+fn classify_param(param: &str) -> Option<&'static str> {
+    let key = param_key(param);
+    let value = param.split_once('=').map(|(_, value)| value);
+    let disabled = matches!(value, Some("0" | "off" | "n" | "N" | "no"));
+
+    let issue = match key.as_str() {
+        "audit" if disabled => "disables_audit",
+        "lockdown" if value == Some("none") => "disables_lockdown",
+        "ima_appraise_tcb" if disabled => "disables_ima",
+        "ima" if disabled => "disables_ima",
+        "ima_appraise" if matches!(value, Some("off" | "fix" | "log")) => "weakens_ima_appraisal",
+        "module.sig_enforce" if disabled => "disables_module_signing",
+        "selinux" if disabled => "disables_selinux",
+        "enforcing" if disabled => "selinux_permissive",
+        "apparmor" if disabled => "disables_apparmor",
+        "security" => "overrides_lsm",
+        "lsm" => "overrides_lsm",
+        "init" => "custom_init",
+        "rdinit" => "custom_rdinit",
+        "modprobe.blacklist" | "module_blacklist" => "blacklists_modules",
+        "rd.break" => "initramfs_break",
+        "rd.shell" if !disabled => "initramfs_shell",
+        _ => return None,
+    };
+    Some(issue)
+}
+
+/// The entry whose kernel matches `BOOT_IMAGE=`, else the default entry,
+/// else every entry on disk.
+fn reference_params(entries: &[BootEntry], boot_image: Option<&str>) -> BTreeSet<String> {
+    let matched: Vec<&BootEntry> = match boot_image {
+        Some(image) => entries
+            .iter()
+            .filter(|entry| entry.kernel.as_deref().map(basename) == Some(basename(image)))
+            .collect(),
+        None => entries.iter().filter(|entry| entry.default).collect(),
+    };
+    let chosen = if matched.is_empty() {
+        entries.iter().collect()
+    } else {
+        matched
+    };
+    chosen
+        .into_iter()
+        .flat_map(|entry| entry.params.iter().cloned())
+        .collect()
+}
+
+fn collect_boot_entries(errors: &mut Vec<String>) -> Vec<BootEntry> {
+    let mut grub_configs: Vec<String> = GRUB_CONFIGS.iter().map(|p| p.to_string()).collect();
+    if let Ok(entries) = fs::read_dir(EFI_VENDOR_DIR) {
+        for entry in entries.flatten() {
+            grub_configs.push(entry.path().join("grub.cfg").to_string_lossy().into_owned());
+        }
+    }
+    let grub_configs: Vec<(String, String)> = grub_configs
+        .into_iter()
+        .filter_map(|path| read_optional(&path, errors).map(|content| (path, content)))
+        .collect();
+    let grubenvs: Vec<(&str, String)> = GRUBENV_FILES
+        .iter()
+        .filter_map(|path| read_optional(path, errors).map(|content| (*path, content)))
+        .collect();
+    let variables = collect_grub_variables(&grub_configs, &grubenvs);
+
+    let expand = |line: &str| -> BTreeSet<String> {
+        tokenize(line)
+            .into_iter()
+            .flat_map(|param| expand_grub_variables(&param, &variables, 0))
+            .flat_map(|expanded| tokenize(&expanded))
+            .collect()
+    };
+    let mut entries = Vec::new();
+    let mut add = |kernel: Option<&str>, default: bool, line: &str| {
+        entries.push(BootEntry {
+            kernel: kernel.map(str::to_string),
+            default,
+            params: expand(line),
+        });
+    };
+
+    if let Some(content) = read_optional(KERNEL_CMDLINE_FILE, errors) {
+        add(None, false, &content);
+    }
+
+    if let Some(content) = read_optional(GRUB_DEFAULTS, errors) {
+        for line in content.lines() {
+            let Some((name, value)) = line.trim().split_once('=') else {
+                continue;
+            };
+            if matches!(name, "GRUB_CMDLINE_LINUX" | "GRUB_CMDLINE_LINUX_DEFAULT") {
+                add(None, false, strip_shell_quotes(value));
+            }
+        }
+    }
+
+    // GRUB_DEFAULT=0 boots the first menu entry of the active grub.cfg.
+    let mut first_grub_entry = true;
+    for (_, content) in &grub_configs {
+        for line in content.lines() {
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("linux" | "linuxefi" | "linux16") => {
+                    let kernel = words.next();
+                    add(
+                        kernel,
+                        first_grub_entry,
+                        &words.collect::<Vec<_>>().join(" "),
+                    );
+                    first_grub_entry = false;
+                }
+                Some("set") if line.trim_start().starts_with("set kernelopts=") => {
+                    let value = line.trim_start().trim_start_matches("set kernelopts=");
+                    add(None, false, strip_shell_quotes(value));
+                }
+                _ => {}
+            }
+        }
+    }
 
-    let cmdline = content.trim();
-    if cmdline.contains("audit=0") {
-        findings.push("flag=audit=0".to_string());
+    for (_, content) in &grubenvs {
+        for line in content.lines() {
+            if let Some(value) = line.strip_prefix("kernelopts=") {
+                add(None, false, value);
+            }
+        }
     }
-    if cmdline.contains("lockdown=none") {
-        findings.push("flag=lockdown=none".to_string());
+
+    for dir in LOADER_ENTRY_DIRS {
+        let Ok(dir_entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        let default = Path::new(dir)
+            .parent()
+            .and_then(|loader| fs::read_to_string(loader.join("loader.conf")).ok())
+            .and_then(|conf| {
+                conf.lines().find_map(|line| {
+                    line.trim()
+                        .strip_prefix("default")
+                        .map(|value| value.trim().to_string())
+                })
+            });
+        for dir_entry in dir_entries.flatten() {
+            let path = dir_entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("conf") {
+                continue;
+            }
+            let name = dir_entry.file_name().to_string_lossy().into_owned();
+            let is_default = default.as_deref().is_some_and(|pattern| {
+                name == pattern
+                    || name.strip_suffix(".conf") == Some(pattern)
+                    || pattern
+                        .strip_suffix('*')
+                        .is_some_and(|prefix| name.starts_with(prefix))
+            });
+            let path = path.to_string_lossy().into_owned();
+            let Some(content) = read_optional(&path, errors) else {
+                continue;
+            };
+            let mut kernel = None;
+            let mut options = Vec::new();
+            for line in content.lines() {
+                let mut words = line.trim().splitn(2, char::is_whitespace);
+                match (words.next(), words.next()) {
+                    (Some("linux"), Some(value)) => kernel = Some(value.trim()),
+                    (Some("options"), Some(value)) => options.push(value),
+                    _ => {}
+                }
+            }
+            add(kernel, is_default, &options.join(" "));
+        }
     }
-    if cmdline.contains("ima_appraise_tcb=0") {
-        findings.push("flag=ima_appraise_tcb=0".to_string());
+
+    entries
+}
+
+fn collect_grub_variables(
+    grub_configs: &[(String, String)],
+    grubenvs: &[(&str, String)],
+) -> BTreeMap<String, BTreeSet<String>> {
+    let mut variables: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let assignments = grub_configs
+        .iter()
+        .flat_map(|(_, content)| content.lines())
+        .filter_map(|line| line.trim().strip_prefix("set "))
+        .chain(
+            grubenvs
+                .iter()
+                .flat_map(|(_, content)| content.lines())
+                .filter(|line| !line.starts_with('#')),
+        );
+    for assignment in assignments {
+        if let Some((name, value)) = assignment.trim().split_once('=') {
+            variables
+                .entry(name.to_string())
+                .or_default()
+                .insert(strip_shell_quotes(value).to_string());
+        }
+    }
+    variables
+}
+
+/// Unknown variables yield nothing so they never match a live parameter.
+fn expand_grub_variables(
+    param: &str,
+    variables: &BTreeMap<String, BTreeSet<String>>,
+    depth: usize,
+) -> Vec<String> {
+    let Some(dollar) = param.find('$') else {
+        return vec![param.to_string()];
+    };
+    if depth > 4 {
+        return Vec::new();
+    }
+    let rest = &param[dollar + 1..];
+    let (name, tail) = match rest.strip_prefix('{') {
+        Some(inner) => match inner.split_once('}') {
+            Some(split) => split,
+            None => return Vec::new(),
+        },
+        None => {
+            let end = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            rest.split_at(end)
+        }
+    };
+    let Some(values) = variables.get(name) else {
+        return Vec::new();
+    };
+    values
+        .iter()
+        .flat_map(|value| {
+            let expanded = format!("{}{}{}", &param[..dollar], value, tail);
+            expand_grub_variables(&expanded, variables, depth + 1)
+        })
+        .collect()
+}
+
+fn inspect_initramfs_hooks(findings: &mut Vec<String>, errors: &mut Vec<String>) {
+    for dir in LOCAL_HOOK_DIRS {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => {
+                errors.push(format!("failed to read {dir}: {err}"));
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name == "ORDER" || name.starts_with('.') || !path.is_file() {
+                continue;
+            }
+            let path = path.to_string_lossy().into_owned();
+            let mut issues = vec!["local_hook"];
+            match investigate_target(&path) {
+                Ok(target_issues) => issues.extend(target_issues),
+                Err(err) => errors.push(err),
+            }
+            if fs::read(&path).is_ok_and(|data| references_temporary(&data)) {
+                issues.push("references_temporary");
+            }
+            findings.push(format!(
+                "initramfs_hook={}, issues={}",
+                path,
+                issues.join("|")
+            ));
+        }
     }
-    if let Some(lsm_idx) = cmdline.find("lsm=") {
-        let tail = &cmdline[lsm_idx..];
-        let value: String = tail
-            .chars()
-            .skip_while(|c| *c != '=')
-            .skip(1)
-            .take_while(|c| !c.is_whitespace())
+}
+
+fn inspect_initramfs_items(findings: &mut Vec<String>, errors: &mut Vec<String>) {
+    let mut configs = vec![DRACUT_CONF.to_string(), MKINITCPIO_CONF.to_string()];
+    if let Ok(entries) = fs::read_dir(DRACUT_CONF_DIR) {
+        let mut extra: Vec<String> = entries
+            .flatten()
+            .map(|entry| entry.path().to_string_lossy().into_owned())
+            .filter(|path| path.ends_with(".conf"))
             .collect();
-        if !value.is_empty() {
-            findings.push(format!("flag=lsm={}", value));
+        extra.sort();
+        configs.extend(extra);
+    }
+
+    for config in &configs {
+        let Some(content) = read_optional(config, errors) else {
+            continue;
+        };
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((name, value)) = line.split_once('=') else {
+                continue;
+            };
+            let directive = name.trim_end_matches('+').trim();
+            if !matches!(directive, "install_items" | "BINARIES" | "FILES") {
+                continue;
+            }
+            let value = strip_shell_quotes(value).trim_matches(|c| c == '(' || c == ')');
+            for item in value.split_whitespace() {
+                if !item.starts_with('/') {
+                    continue;
+                }
+                let mut issues = Vec::new();
+                if is_temporary(item) {
+                    issues.push("temporary_path");
+                }
+                match investigate_target(item) {
+                    Ok(target_issues) => issues.extend(target_issues),
+                    Err(err) => errors.push(err),
+                }
+                if !issues.is_empty() {
+                    findings.push(format!(
+                        "initramfs_item={}, config={}, directive={}, issues={}",
+                        item,
+                        config,
+                        directive,
+                        issues.join("|")
+                    ));
+                }
+            }
         }
     }
+}
 
-    if findings.is_empty() {
-        Ok(None)
-    } else {
-        Ok(Some(findings.join(", ")))
+fn basename(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn read_optional(path: &str, errors: &mut Vec<String>) -> Option<String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Some(content),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => {
+            if Path::new(path).exists() {
+                errors.push(format!("failed to read {path}: {err}"));
+            }
+            None
+        }
     }
 }
+
+fn strip_shell_quotes(value: &str) -> &str {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|inner| inner.strip_suffix('"'))
+        .or_else(|| {
+            value
+                .strip_prefix('\'')
+                .and_then(|inner| inner.strip_suffix('\''))
+        })
+        .unwrap_or(value)
+}
+
+fn is_temporary(path: &str) -> bool {
+    path.starts_with("/tmp/") || path.starts_with("/var/tmp/") || path.starts_with("/dev/shm/")
+}
+
+fn references_temporary(data: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(data);
+    ["/tmp/", "/var/tmp/", "/dev/shm/"]
+        .iter()
+        .any(|needle| text.contains(needle))
+}
@@ -1,9 +1,20 @@
+use super::{
+    hidden_lkm::collect_proc_modules,
+    kmsg_utils::{KmsgRecord, read_kmsg},
+};
 use crate::ScanOutcome;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashSet},
     fs, io,
 };
 
+/// A module the kernel log names as the one that set a taint bit.
+pub struct TaintEvent {
+    pub letter: char,
+    pub module: String,
+    pub ts: String,
+}
+
 pub fn run() -> ScanOutcome {
     let raw_taint = fs::read_to_string("/proc/sys/kernel/tainted")
         .map_err(|err| format!("failed to read /proc/sys/kernel/tainted: {err}"))?;
@@ -17,63 +28,186 @@ pub fn run() -> ScanOutcome {
         return Ok(None);
     }
 
-    let module_taints = collect_module_taint_letters()
-        .map_err(|err| format!("failed to collect module taints: {err}"))?;
+    let visible =
+        collect_module_taints().map_err(|err| format!("failed to collect module taints: {err}"))?;
+    // Kernels built without module support have no /proc/modules at all.
+    let loaded = match collect_proc_modules() {
+        Ok(modules) => modules,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => HashSet::new(),
+        Err(err) => return Err(format!("failed to read /proc/modules: {err}")),
+    };
+
+    let mut errors = Vec::new();
+    let mut log_gap = None;
+    let events = match read_kmsg() {
+        Ok(snapshot) => {
+            // Early records are gone once the ring wraps or is cleared, so
+            // the first taint messages may no longer be attributable.
+            match snapshot.records.first() {
+                Some(first) if first.seq > 0 => log_gap = Some(first.seq),
+                _ if snapshot.overwritten > 0 => log_gap = Some(0),
+                _ => {}
+            }
+            snapshot.records.iter().filter_map(taint_event).collect()
+        }
+        Err(err) => {
+            errors.push(err);
+            Vec::new()
+        }
+    };
 
     let mut active_flags = Vec::new();
-    let mut missing_module_flags = Vec::new();
+    let mut findings = Vec::new();
 
     for flag in TAINT_FLAGS {
-        if (taint_value & (1u64 << flag.bit)) != 0 {
-            active_flags.push(flag);
-            if flag.module_related && !module_taints.contains_key(&flag.letter) {
-                missing_module_flags.push(flag);
-            }
+        if (taint_value & (1u64 << flag.bit)) == 0 {
+            continue;
         }
+        active_flags.push(flag);
+        if !flag.module_related
+            || visible
+                .values()
+                .any(|letters| letters.contains(&flag.letter))
+        {
+            continue;
+        }
+
+        let attribution = match events.iter().find(|event| event.letter == flag.letter) {
+            Some(event) => format!(
+                "taint_source={}, loaded_at={}, source_state={}",
+                event.module,
+                event.ts,
+                module_state(&event.module, &visible, &loaded)
+            ),
+            None => match log_gap {
+                Some(seq) => format!("taint_source=unknown (kmsg truncated before seq {seq})"),
+                None => "taint_source=unknown".to_string(),
+            },
+        };
+        findings.push(format!(
+            "flag={} ({}), visible_modules=∅, {}",
+            flag.letter, flag.description, attribution
+        ));
     }
 
-    if missing_module_flags.is_empty() {
-        return Ok(None);
+    // Taint bits some visible module explains can still have been set first
+    // by a module that has since gone away.
+    for event in &events {
+        if !visible
+            .values()
+            .any(|letters| letters.contains(&event.letter))
+        {
+            continue;
+        }
+        let state = module_state(&event.module, &visible, &loaded);
+        if state != "loaded" {
+            findings.push(format!(
+                "taint_event={}, module={}, at={}, module_state={}",
+                event.letter, event.module, event.ts, state
+            ));
+        }
     }
 
-    let active_letters: String = active_flags.iter().map(|flag| flag.letter).collect();
-    let missing_descriptions: Vec<String> = missing_module_flags
-        .iter()
-        .map(|flag| format!("{} ({})", flag.letter, flag.description))
-        .collect();
-
-    let mut message = format!(
-        "tainted={} active_flags={}",
-        taint_value,
-        if active_letters.is_empty() {
-            "none"
+    for (module, letters) in &visible {
+        let letters: String = letters.iter().collect();
+        let status: Vec<&str> = letters.chars().map(module_status).collect();
+        let first_seen = events
+            .iter()
+            .find(|event| &event.module == module)
+            .map(|event| format!(", first_taint_at={}", event.ts))
+            .unwrap_or_default();
+        findings.push(format!(
+            "module={}, taint={}, status={}{}",
+            module,
+            letters,
+            status.join("|"),
+            first_seen
+        ));
+    }
+
+    if findings.is_empty() {
+        return if errors.is_empty() {
+            Ok(None)
         } else {
-            active_letters.as_str()
-        }
+            Err(errors.join(", "))
+        };
+    }
+
+    let active_letters: String = active_flags.iter().map(|flag| flag.letter).collect();
+    findings.insert(
+        0,
+        format!("tainted={}, active_flags={}", taint_value, active_letters),
     );
+    if !errors.is_empty() {
+        findings.push(format!("collection_errors={}", errors.join(", ")));
+    }
+    Ok(Some(findings.join("\n")))
+}
 
-    message.push_str(" missing_module_flags=");
-    message.push_str(&missing_descriptions.join(", "));
+/// Recognises the messages the module loader prints when it taints the
+/// kernel. Most are printed once per taint bit, so the first match is the
+/// module that set it.
+pub fn taint_event(record: &KmsgRecord) -> Option<TaintEvent> {
+    // Userspace can write look-alike lines to /dev/kmsg, but never as the
+    // kernel facility.
+    if record.facility != 0 {
+        return None;
+    }
+    let (module, text) = record.message.split_once(": ")?;
+    let letter = if text.starts_with("loading out-of-tree module taints kernel") {
+        'O'
+    } else if text.starts_with("module verification failed") {
+        'E'
+    } else if text.starts_with("module license") && text.contains("taints kernel") {
+        'P'
+    } else if text.starts_with("module is from the staging directory") {
+        'C'
+    } else if text.ends_with(": kernel tainted.") {
+        'F'
+    } else if text.starts_with("tainting kernel with TAINT_LIVEPATCH") {
+        'K'
+    } else {
+        return None;
+    };
+    if module.is_empty() || module.contains(char::is_whitespace) {
+        return None;
+    }
+    Some(TaintEvent {
+        letter,
+        module: module.to_string(),
+        ts: record.timestamp(),
+    })
+}
 
-    if !module_taints.is_empty() {
-        let mut visible: Vec<String> = module_taints
-            .iter()
-            .map(|(letter, modules)| {
-                let mut modules = modules.clone();
-                modules.sort();
-                format!("{} -> {}", letter, modules.join("|"))
-            })
-            .collect();
-        visible.sort();
-        message.push_str(" visible=");
-        message.push_str(&visible.join("; "));
+fn module_state(
+    module: &str,
+    visible: &BTreeMap<String, BTreeSet<char>>,
+    loaded: &HashSet<String>,
+) -> &'static str {
+    match (visible.contains_key(module), loaded.contains(module)) {
+        (_, true) => "loaded",
+        (true, false) => "hidden_from_proc_modules",
+        (false, false) => "not_loaded",
     }
+}
 
-    Ok(Some(message))
+fn module_status(letter: char) -> &'static str {
+    match letter {
+        'P' => "proprietary",
+        'F' => "forced_load",
+        'R' => "forced_unload",
+        'C' => "staging",
+        'O' => "out_of_tree",
+        'E' => "unsigned",
+        'K' => "livepatch",
+        _ => "other",
+    }
 }
 
-fn collect_module_taint_letters() -> io::Result<HashMap<char, Vec<String>>> {
-    let mut map: HashMap<char, HashSet<String>> = HashMap::new();
+/// Taint letters per module, merged from `/sys/module/*/taint` and the
+/// parenthesised flags in `/proc/modules`.
+fn collect_module_taints() -> io::Result<BTreeMap<String, BTreeSet<char>>> {
+    let mut map: BTreeMap<String, BTreeSet<char>> = BTreeMap::new();
 
     for entry in fs::read_dir("/sys/module")? {
         let entry = entry?;
@@ -82,13 +216,18 @@ fn collect_module_taint_letters() -> io::Result<HashMap<char, Vec<String>>> {
         if let Ok(content) = fs::read_to_string(&taint_path) {
             for ch in content.trim().chars() {
                 if ch.is_ascii_alphabetic() {
-                    map.entry(ch).or_default().insert(name.clone());
+                    map.entry(name.clone()).or_default().insert(ch);
                 }
             }
         }
     }
 
-    let modules = fs::read_to_string("/proc/modules")?;
+    // Kernels built without module support have no /proc/modules.
+    let modules = match fs::read_to_string("/proc/modules") {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
     for line in modules.lines() {
         let module_name = line.split_whitespace().next().unwrap_or_default();
         if module_name.is_empty() {
@@ -100,21 +239,14 @@ fn collect_module_taint_letters() -> io::Result<HashMap<char, Vec<String>>> {
                 let letters = &line[start + 1..start + 1 + end_offset];
                 for ch in letters.chars() {
                     if ch.is_ascii_alphabetic() {
-                        map.entry(ch).or_default().insert(module_name.to_string());
+                        map.entry(module_name.to_string()).or_default().insert(ch);
                     }
                 }
             }
         }
     }
 
-    let mut result: HashMap<char, Vec<String>> = HashMap::new();
-    for (letter, modules) in map {
-        let mut names: Vec<String> = modules.into_iter().collect();
-        names.sort();
-        result.insert(letter, names);
-    }
-
-    Ok(result)
+    Ok(map)
 }
 
 #[derive(Clone, Copy)]
@@ -0,0 +1,311 @@
+use super::hidden_lkm::{collect_kallsyms_modules, collect_proc_modules, collect_sysfs_modules};
+use super::kernel_taint::taint_event;
+use super::kmsg_utils::{KmsgRecord, KmsgSnapshot, read_kmsg};
+use crate::ScanOutcome;
+use std::collections::{BTreeMap, HashSet};
+
+const SYSLOG_ACTION_READ_ALL: libc::c_int = 3;
+const SYSLOG_ACTION_SIZE_BUFFER: libc::c_int = 10;
+const MAX_MESSAGE_LEN: usize = 160;
+const RECORD_OVERHEAD: usize = 96;
+const SYSLOG_BUFFER_FACTOR: usize = 4;
+const SYSLOG_LINE_MAX: usize = 1024 + 32;
+
+const EVENT_PATTERNS: &[(&str, &str)] = &[
+    (
+        "is installing a program with bpf_probe_write_user helper",
+        "bpf_probe_write_user",
+    ),
+    ("trace_printk() being used", "trace_printk_enabled"),
+    (
+        "never use this in production, only for jit debugging",
+        "bpf_jit_debug_enabled",
+    ),
+    ("could not insert probe at", "kprobe_register_failed"),
+    (
+        "failed to register probe event",
+        "probe_event_register_failed",
+    ),
+    ("failed to unregister, leaking uprobe", "uprobe_leaked"),
+    ("core dump to |", "usermode_helper_core_pipe"),
+    ("request_module:", "usermode_helper_modprobe"),
+    ("audit: backlog limit exceeded", "audit_backlog_exceeded"),
+    ("audit: rate limit exceeded", "audit_rate_limited"),
+    ("audit: audit_lost=", "audit_lost"),
+    ("kauditd hold queue overflow", "audit_hold_queue_overflow"),
+    ("lockdown: ", "lockdown_denied"),
+    ("was attempted by", "ptrace_denied"),
+    ("started with executable stack", "exec_stack"),
+    ("unknown symbol", "module_load_failed"),
+    (
+        "disagrees about version of symbol",
+        "module_version_mismatch",
+    ),
+];
+
+struct ModuleViews {
+    proc_modules: Option<HashSet<String>>,
+    sysfs: HashSet<String>,
+    kallsyms: HashSet<String>,
+}
+
+impl ModuleViews {
+    fn collect() -> Self {
+        Self {
+            proc_modules: collect_proc_modules().ok(),
+            sysfs: collect_sysfs_modules().unwrap_or_default(),
+            kallsyms: collect_kallsyms_modules().unwrap_or_default(),
+        }
+    }
+
+    fn state(&self, module: &str) -> &'static str {
+        match &self.proc_modules {
+            None => "unknown",
+            Some(listed) if listed.contains(module) => "loaded",
+            Some(_) if self.sysfs.contains(module) || self.kallsyms.contains(module) => {
+                "hidden(missing_in=proc_modules)"
+            }
+            Some(_) => "not_loaded",
+        }
+    }
+}
+
+struct Event {
+    first_seen: String,
+    count: usize,
+}
+
+pub fn run() -> ScanOutcome {
+    let snapshot = read_kmsg()?;
+
+    let mut findings = Vec::new();
+    let mut errors = Vec::new();
+
+    inspect_sequence(&snapshot, &mut findings, &mut errors);
+
+    let modules = ModuleViews::collect();
+    let mut events: BTreeMap<(String, String), Event> = BTreeMap::new();
+    for record in &snapshot.records {
+        let Some((kind, detail)) = classify(record, &modules) else {
+            continue;
+        };
+        events
+            .entry((kind, detail))
+            .and_modify(|event| event.count += 1)
+            .or_insert_with(|| Event {
+                first_seen: record.timestamp(),
+                count: 1,
+            });
+    }
+
+    for ((kind, detail), event) in events {
+        findings.push(format!(
+            "event={}, at={}, count={}, {}",
+            kind, event.first_seen, event.count, detail
+        ));
+    }
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+fn classify(record: &KmsgRecord, modules: &ModuleViews) -> Option<(String, String)> {
+    let message = truncate(&record.message);
+
+    if let Some(kind) = pattern_kind(&record.message) {
+        // Daemons log through /dev/kmsg as `name[pid]: ...`.
+        if record.facility != 0 {
+            if has_userspace_prefix(&record.message) {
+                return None;
+            }
+            return Some((
+                "spoofed_kernel_message".to_string(),
+                format!(
+                    "imitates={kind}, facility={}, message={message}",
+                    record.facility
+                ),
+            ));
+        }
+        let detail = match kind {
+            "module_load_failed" | "module_version_mismatch" => {
+                let module = record.message.split(": ").next().unwrap_or_default();
+                format!(
+                    "module={}, module_state={}, message={}",
+                    module,
+                    modules.state(module),
+                    message
+                )
+            }
+            _ => format!("message={message}"),
+        };
+        return Some((kind.to_string(), detail));
+    }
+
+    if record.facility != 0 {
+        return None;
+    }
+
+    if let Some(event) = taint_event(record) {
+        let state = modules.state(&event.module);
+        return (state != "loaded").then(|| {
+            (
+                format!("module_taint({})", event.letter),
+                format!("module={}, module_state={}", event.module, state),
+            )
+        });
+    }
+
+    crash_kind(&record.message).map(|kind| (kind.to_string(), format!("message={message}")))
+}
+
+fn has_userspace_prefix(message: &str) -> bool {
+    let Some((tag, _)) = message.split_once(": ") else {
+        return false;
+    };
+    tag.strip_suffix(']')
+        .and_then(|rest| rest.rsplit_once('['))
+        .is_some_and(|(name, pid)| {
+            !name.is_empty()
+                && !name.contains(char::is_whitespace)
+                && !pid.is_empty()
+                && pid.bytes().all(|b| b.is_ascii_digit())
+        })
+}
+
+fn pattern_kind(message: &str) -> Option<&'static str> {
+    let lower = message.to_ascii_lowercase();
+    EVENT_PATTERNS
+        .iter()
+        .find(|(pattern, _)| lower.contains(pattern))
+        .map(|(_, kind)| *kind)
+}
+
+/// The kernel only appends " in <file>[base+size]" when the faulting VMA is
+/// file backed.
+fn crash_kind(message: &str) -> Option<&'static str> {
+    let rest = if let Some(idx) = message.find(": segfault at ") {
+        &message[idx..]
+    } else if let Some(rest) = message.strip_prefix("traps: ") {
+        rest
+    } else if let Some(idx) = message.find("general protection fault ip:") {
+        &message[idx..]
+    } else {
+        return None;
+    };
+
+    match rest.split_once(" in ") {
+        None => Some("crash_in_anonymous_code"),
+        Some((_, target)) if target.starts_with("memfd:") || target.contains("(deleted)") => {
+            Some("crash_in_volatile_mapping")
+        }
+        Some(_) => None,
+    }
+}
+
+/// Sequence numbers are contiguous for the life of the boot.
+fn inspect_sequence(snapshot: &KmsgSnapshot, findings: &mut Vec<String>, errors: &mut Vec<String>) {
+    let records = &snapshot.records;
+    let Some(first) = records.first() else {
+        return;
+    };
+
+    for pair in records.windows(2) {
+        if pair[1].seq > pair[0].seq + 1 {
+            findings.push(format!(
+                "log_gap=seq {}..{}, missing={}, between={}..{}, overwritten_during_read={}",
+                pair[0].seq + 1,
+                pair[1].seq - 1,
+                pair[1].seq - pair[0].seq - 1,
+                pair[0].timestamp(),
+                pair[1].timestamp(),
+                snapshot.overwritten
+            ));
+        }
+    }
+
+    let buffer_size = unsafe { libc::klogctl(SYSLOG_ACTION_SIZE_BUFFER, std::ptr::null_mut(), 0) };
+    if buffer_size <= 0 {
+        errors.push(format!(
+            "syslog(SIZE_BUFFER): {}",
+            std::io::Error::last_os_error()
+        ));
+        return;
+    }
+
+    if first.seq > 0 {
+        let used: usize = records
+            .iter()
+            .map(|record| record.message.len() + RECORD_OVERHEAD)
+            .sum();
+        if used < buffer_size as usize / 2 {
+            findings.push(format!(
+                "log_head_missing=seq 0..{}, ring_used~{}B of {}B, issue=head_missing_without_pressure",
+                first.seq - 1,
+                used,
+                buffer_size
+            ));
+        }
+    }
+
+    let mut buf = vec![0u8; buffer_size as usize * SYSLOG_BUFFER_FACTOR];
+    let len = unsafe {
+        libc::klogctl(
+            SYSLOG_ACTION_READ_ALL,
+            buf.as_mut_ptr() as *mut libc::c_char,
+            buf.len() as libc::c_int,
+        )
+    };
+    if len < 0 {
+        errors.push(format!(
+            "syslog(READ_ALL): {}",
+            std::io::Error::last_os_error()
+        ));
+        return;
+    }
+    // A nearly full buffer may have dropped the oldest lines to fit.
+    if len as usize + SYSLOG_LINE_MAX > buf.len() {
+        return;
+    }
+    buf.truncate(len as usize);
+    let text = String::from_utf8_lossy(&buf);
+
+    let first_ts = first.ts_usec as f64 / 1_000_000.0;
+    match text.lines().next().map(syslog_timestamp) {
+        None => findings.push(format!(
+            "syslog_cleared=all, kmsg_starts_at={}, issue=dmesg_cleared",
+            first.timestamp()
+        )),
+        Some(Some(ts)) if ts > first_ts + 0.000_001 => findings.push(format!(
+            "syslog_cleared=before +{:.6}s, kmsg_starts_at={}, issue=dmesg_cleared",
+            ts,
+            first.timestamp()
+        )),
+        _ => {}
+    }
+}
+
+fn syslog_timestamp(line: &str) -> Option<f64> {
+    let start = line.find('[')?;
+    let end = line[start..].find(']')? + start;
+    line[start + 1..end].trim().parse().ok()
+}
+
+fn truncate(message: &str) -> String {
+    if message.chars().count() <= MAX_MESSAGE_LEN {
+        message.to_string()
+    } else {
+        let head: String = message.chars().take(MAX_MESSAGE_LEN).collect();
+        format!("{head}...")
+    }
+}
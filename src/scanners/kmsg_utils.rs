@@ -0,0 +1,79 @@
+use std::{
+    fs::OpenOptions,
+    io::{self, Read},
+    os::unix::fs::OpenOptionsExt,
+};
+
+const KMSG_PATH: &str = "/dev/kmsg";
+const KMSG_RECORD_MAX: usize = 8192;
+
+pub struct KmsgRecord {
+    /// Userspace writes through /dev/kmsg are at least LOG_USER.
+    pub facility: u32,
+    pub seq: u64,
+    pub ts_usec: u64,
+    pub message: String,
+}
+
+impl KmsgRecord {
+    pub fn timestamp(&self) -> String {
+        format!(
+            "+{}.{:06}s",
+            self.ts_usec / 1_000_000,
+            self.ts_usec % 1_000_000
+        )
+    }
+}
+
+pub struct KmsgSnapshot {
+    pub records: Vec<KmsgRecord>,
+    /// Reads that failed with `EPIPE` because the kernel dropped records.
+    pub overwritten: usize,
+}
+
+pub fn read_kmsg() -> Result<KmsgSnapshot, String> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(KMSG_PATH)
+        .map_err(|err| format!("failed to open {KMSG_PATH}: {err}"))?;
+
+    let mut records = Vec::new();
+    let mut overwritten = 0;
+    let mut buf = vec![0u8; KMSG_RECORD_MAX];
+    loop {
+        match file.read(&mut buf) {
+            Ok(0) => break,
+            Ok(len) => {
+                if let Some(record) = parse_record(&String::from_utf8_lossy(&buf[..len])) {
+                    records.push(record);
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) if err.raw_os_error() == Some(libc::EPIPE) => overwritten += 1,
+            Err(err) => return Err(format!("failed to read {KMSG_PATH}: {err}")),
+        }
+    }
+
+    Ok(KmsgSnapshot {
+        records,
+        overwritten,
+    })
+}
+
+fn parse_record(raw: &str) -> Option<KmsgRecord> {
+    let (header, rest) = raw.split_once(';')?;
+    let mut fields = header.split(',');
+    let prio = fields.next()?.parse::<u32>().ok()?;
+    let seq = fields.next()?.parse::<u64>().ok()?;
+    let ts_usec = fields.next()?.parse::<u64>().ok()?;
+    let message = rest.lines().next().unwrap_or_default().to_string();
+
+    Some(KmsgRecord {
+        facility: prio >> 3,
+        seq,
+        ts_usec,
+        message,
+    })
+}
@@ -1,8 +1,60 @@
 use crate::ScanOutcome;
-use std::fs;
+use std::{
+    fs,
+    os::unix::fs::{FileExt, MetadataExt},
+};
 
 const MIN_SIZE: u64 = 65536;
-const MAX_PROCESSES: usize = 256;
+const REGION_READ_LIMIT: u64 = 1 << 20;
+const DATA_READ_LIMIT: u64 = 4 << 20;
+const PAGE_SIZE: usize = 4096;
+const SYSCALL_MIN_COUNT: usize = 8;
+const SYSCALL_MIN_PER_MIB: f64 = 64.0;
+const HIGH_ENTROPY: f64 = 7.2;
+
+// Executables that are JIT runtimes themselves; their whole address space
+// is expected to hold anonymous RX code.
+const JIT_EXECUTABLES: &[&str] = &[
+    "node", "java", "luajit", "pypy", "dotnet", "mono", "beam.smp", "julia", "hhvm", "chrome",
+    "electron",
+];
+
+// Embeddable JIT libraries. Mapping one only exempts the RX regions its own
+// data segment points into, never the rest of the process.
+const JIT_LIBRARIES: &[&str] = &[
+    "libjvm",
+    "libnode",
+    "libv8",
+    "libmozjs",
+    "libjavascriptcoregtk",
+    "libluajit",
+    "libcoreclr",
+    "libclrjit",
+    "libmonosgen",
+    "libpypy",
+    "libQt5Qml",
+    "libQt6Qml",
+    "libjulia",
+    "libhhvm",
+];
+
+// Prologues of common reflective loaders and stagers.
+const LOADER_STUBS: &[(&[u8], &str)] = &[
+    (&[0xfc, 0x48, 0x83, 0xe4, 0xf0], "msf_x64_stager"),
+    (&[0xfc, 0xe8, 0x82, 0x00, 0x00, 0x00], "msf_x86_stager"),
+    (&[0xfc, 0xe8, 0x89, 0x00, 0x00, 0x00], "msf_x86_stager"),
+    (
+        &[0x4d, 0x5a, 0x41, 0x52, 0x55, 0x48],
+        "reflective_dll_bootstrap",
+    ),
+];
+
+struct Mapping {
+    start: u64,
+    end: u64,
+    perms: String,
+    path: String,
+}
 
 pub fn run() -> ScanOutcome {
     let mut findings = Vec::new();
@@ -11,14 +63,13 @@ pub fn run() -> ScanOutcome {
         Err(err) => return Err(format!("failed to read /proc: {err}")),
     };
 
-    for entry in proc_dir.flatten().take(MAX_PROCESSES) {
+    for entry in proc_dir.flatten() {
         let pid: i32 = match entry.file_name().to_str().and_then(|s| s.parse().ok()) {
             Some(pid) => pid,
             None => continue,
         };
-        match inspect_process(pid) {
-            Ok(mut list) => findings.append(&mut list),
-            Err(_) => {}
+        if let Ok(mut list) = inspect_process(pid) {
+            findings.append(&mut list);
         }
     }
 
@@ -44,52 +95,282 @@ fn inspect_process(pid: i32) -> Result<Vec<String>, String> {
 
     let maps_path = format!("/proc/{pid}/maps");
     let maps = fs::read_to_string(&maps_path).map_err(|err| format!("{maps_path}: {err}"))?;
-    let mut jit_markers = false;
-    if maps.contains("libjvm") || maps.contains("v8") || maps.contains("jit") {
-        jit_markers = true;
-    }
+    let mappings = parse_maps(&maps);
 
-    if jit_markers {
+    let exe = fs::read_link(format!("/proc/{pid}/exe"))
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if trusted_jit_executable(&exe) {
         return Ok(Vec::new());
     }
+    let jit_libraries = jit_libraries(&mappings);
 
     let mut findings = Vec::new();
+    let mut memory = None;
 
-    for line in maps.lines() {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() < 6 {
+    for region in &mappings {
+        if !region.perms.contains('x') || region.perms.contains('s') {
             continue;
         }
-        let range = parts[0];
-        let perms = parts[1];
-        let path = parts[5];
-        if !perms.contains('x') || perms.contains('s') {
+        if !region.path.is_empty()
+            && region.path != "[anon]"
+            && region.path != "[heap]"
+            && region.path != "[stack]"
+        {
             continue;
         }
-        if !path.is_empty() && path != "[anon]" && path != "[heap]" && path != "[stack]" {
+        let size = region.end.saturating_sub(region.start);
+        if size < MIN_SIZE {
             continue;
         }
-        if let Some(size) = mapping_size(range) {
-            if size >= MIN_SIZE {
-                findings.push(format!(
-                    "pid={}, comm={}, anon_rx={} size={}B likely_non_jit=true",
-                    pid,
-                    command(pid),
-                    range,
-                    size
-                ));
+
+        let (mem, segments) = memory.get_or_insert_with(|| {
+            let mem = fs::File::open(format!("/proc/{pid}/mem")).ok();
+            let segments = mem
+                .as_ref()
+                .map(|file| read_data_segments(file, &mappings))
+                .unwrap_or_default();
+            (mem, segments)
+        });
+        let references = referencing_objects(region, segments);
+        if !jit_libraries.is_empty()
+            && !references.is_empty()
+            && references.iter().all(|path| jit_libraries.contains(path))
+        {
+            continue;
+        }
+
+        let mut record = format!(
+            "pid={}, comm={}, anon_rx={:x}-{:x} size={}B likely_non_jit=true",
+            pid,
+            command(pid),
+            region.start,
+            region.end,
+            size
+        );
+        match mem.as_ref() {
+            Some(file) => {
+                record.push_str(&classify_region(file, region, &references));
             }
+            None => record.push_str(", content=unreadable"),
         }
+
+        findings.push(record);
     }
 
     Ok(findings)
 }
 
-fn mapping_size(range: &str) -> Option<u64> {
+fn parse_maps(maps: &str) -> Vec<Mapping> {
+    let mut mappings = Vec::new();
+    for line in maps.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 5 {
+            continue;
+        }
+        let Some((start, end)) = mapping_bounds(parts[0]) else {
+            continue;
+        };
+        mappings.push(Mapping {
+            start,
+            end,
+            perms: parts[1].to_string(),
+            path: parts
+                .get(5..)
+                .map(|rest| rest.join(" "))
+                .unwrap_or_default(),
+        });
+    }
+    mappings
+}
+
+/// Matches `node`, `node18` or `pypy3.10`, but not `node_exporter`, and only
+/// for root-owned binaries outside staging areas.
+fn trusted_jit_executable(exe: &str) -> bool {
+    let name = exe.rsplit('/').next().unwrap_or(exe);
+    let runtime = JIT_EXECUTABLES.iter().any(|prefix| {
+        name.strip_prefix(prefix).is_some_and(|rest| {
+            rest.is_empty()
+                || rest
+                    .trim_start_matches(['.', '-'])
+                    .starts_with(|c: char| c.is_ascii_digit())
+        })
+    });
+    if !runtime
+        || exe.ends_with(" (deleted)")
+        || exe.starts_with("/memfd:")
+        || ["/tmp/", "/var/tmp/", "/dev/shm/"]
+            .iter()
+            .any(|prefix| exe.starts_with(prefix))
+    {
+        return false;
+    }
+    fs::metadata(exe).is_ok_and(|meta| meta.uid() == 0 && meta.mode() & 0o022 == 0)
+}
+
+/// Paths of mapped JIT libraries such as `libjvm.so` or `libnode.so.108`.
+fn jit_libraries(mappings: &[Mapping]) -> Vec<String> {
+    let mut libraries: Vec<String> = Vec::new();
+    for mapping in mappings {
+        if !mapping.path.starts_with('/') || libraries.contains(&mapping.path) {
+            continue;
+        }
+        let name = mapping.path.rsplit('/').next().unwrap_or_default();
+        let matches = JIT_LIBRARIES.iter().any(|prefix| {
+            name.strip_prefix(prefix).is_some_and(|rest| {
+                rest.starts_with(['.', '-']) || rest.starts_with(|c: char| c.is_ascii_digit())
+            })
+        });
+        if matches {
+            libraries.push(mapping.path.clone());
+        }
+    }
+    libraries
+}
+
+fn classify_region(mem: &fs::File, region: &Mapping, references: &[String]) -> String {
+    let length = (region.end - region.start).min(REGION_READ_LIMIT) as usize;
+    let mut buf = vec![0u8; length];
+    let read = match mem.read_at(&mut buf, region.start) {
+        Ok(read) => read,
+        Err(err) => return format!(", content=unreadable({err})"),
+    };
+    buf.truncate(read);
+    if buf.is_empty() {
+        return ", content=unreadable".to_string();
+    }
+
+    let mut labels = Vec::new();
+
+    for offset in (0..buf.len()).step_by(PAGE_SIZE) {
+        let page = &buf[offset..];
+        if page.starts_with(b"\x7fELF") {
+            labels.push(format!("embedded_elf@{:#x}", offset));
+        } else if is_pe_image(page) {
+            labels.push(format!("pe_image@{:#x}", offset));
+        }
+    }
+
+    for (pattern, name) in LOADER_STUBS {
+        if let Some(pos) = find(&buf, pattern) {
+            labels.push(format!("loader_stub={}@{:#x}", name, pos));
+        }
+    }
+
+    let syscalls = count_syscall_instructions(&buf);
+    let per_mib = syscalls as f64 * (1 << 20) as f64 / buf.len() as f64;
+    if syscalls >= SYSCALL_MIN_COUNT && per_mib >= SYSCALL_MIN_PER_MIB {
+        labels.push("syscall_dense".to_string());
+    }
+
+    let entropy = shannon_entropy(&buf);
+    if entropy >= HIGH_ENTROPY {
+        labels.push("high_entropy".to_string());
+    }
+
+    if !references.is_empty() {
+        labels.push(format!("referenced_from={}", references.join("|")));
+    }
+
+    if labels.is_empty() {
+        labels.push("unclassified".to_string());
+    }
+
+    format!(
+        ", scanned={}B, syscall_insns={}, entropy={:.2}, classification={}",
+        buf.len(),
+        syscalls,
+        entropy,
+        labels.join("|")
+    )
+}
+
+fn is_pe_image(page: &[u8]) -> bool {
+    if page.len() < 0x40 || !page.starts_with(b"MZ") {
+        return false;
+    }
+    let e_lfanew = u32::from_le_bytes([page[0x3c], page[0x3d], page[0x3e], page[0x3f]]) as usize;
+    page.get(e_lfanew..e_lfanew + 4) == Some(b"PE\0\0".as_slice())
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn count_syscall_instructions(buf: &[u8]) -> usize {
+    if cfg!(target_arch = "aarch64") {
+        // svc #0
+        buf.chunks_exact(4)
+            .filter(|insn| *insn == [0x01, 0x00, 0x00, 0xd4])
+            .count()
+    } else {
+        // syscall / int 0x80 / sysenter
+        buf.windows(2)
+            .filter(|pair| matches!(pair, [0x0f, 0x05] | [0xcd, 0x80] | [0x0f, 0x34]))
+            .count()
+    }
+}
+
+fn shannon_entropy(buf: &[u8]) -> f64 {
+    let mut counts = [0usize; 256];
+    for byte in buf {
+        counts[*byte as usize] += 1;
+    }
+    let total = buf.len() as f64;
+    counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let p = *count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+// GOT/PLT slots live in the writable data segment of each loaded object.
+fn read_data_segments(mem: &fs::File, mappings: &[Mapping]) -> Vec<(String, Vec<u8>)> {
+    let mut segments = Vec::new();
+    for mapping in mappings {
+        if !mapping.perms.starts_with("rw") || !mapping.path.starts_with('/') {
+            continue;
+        }
+        let length = (mapping.end - mapping.start).min(DATA_READ_LIMIT) as usize;
+        let mut buf = vec![0u8; length];
+        let Ok(read) = mem.read_at(&mut buf, mapping.start) else {
+            continue;
+        };
+        buf.truncate(read);
+        segments.push((mapping.path.clone(), buf));
+    }
+    segments
+}
+
+fn referencing_objects(region: &Mapping, segments: &[(String, Vec<u8>)]) -> Vec<String> {
+    let mut objects = Vec::new();
+    for (path, buf) in segments {
+        if objects.contains(path) {
+            continue;
+        }
+        let hit = buf.chunks_exact(8).any(|slot| {
+            let value = u64::from_le_bytes([
+                slot[0], slot[1], slot[2], slot[3], slot[4], slot[5], slot[6], slot[7],
+            ]);
+            value >= region.start && value < region.end
+        });
+        if hit {
+            objects.push(path.clone());
+        }
+    }
+    objects
+}
+
+fn mapping_bounds(range: &str) -> Option<(u64, u64)> {
     let mut parts = range.split('-');
     let start = u64::from_str_radix(parts.next()?, 16).ok()?;
     let end = u64::from_str_radix(parts.next()?, 16).ok()?;
-    Some(end.saturating_sub(start))
+    Some((start, end))
 }
 
 fn command(pid: i32) -> String {
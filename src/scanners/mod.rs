@@ -1,24 +1,33 @@
+pub mod account_anomalies;
 pub mod audit_disabled;
+pub mod audit_utils;
+pub mod binfmt_misc;
 pub mod bpf_kprobe_attachments;
 pub mod bpf_lsm;
 pub mod container_utils;
 pub mod core_pattern_pipeline;
 pub mod cron_ghost;
 pub mod deleted_memfd;
+pub mod elf_utils;
 pub mod fanotify_watchers;
 pub mod ftrace_redirection;
+pub mod got_hooks;
+pub mod hardening_posture;
 pub mod hidden_bind_mounts;
 pub mod hidden_listeners;
 pub mod hidden_lkm;
 pub mod hidden_pids;
 pub mod host_net_ns;
 pub mod host_pid_ns;
+pub mod initramfs_contents;
 pub mod journal_gaps;
 pub mod kernel_cmdline;
 pub mod kernel_message_suppression;
 pub mod kernel_taint;
 pub mod kernel_text_ro;
 pub mod kernel_thread_masquerade;
+pub mod kmsg_forensics;
+pub mod kmsg_utils;
 pub mod large_rx;
 pub mod ld_audit;
 pub mod ld_so_preload;
@@ -30,16 +39,24 @@ pub mod module_list_linkage_tamper;
 pub mod netfilter_cloaking;
 pub mod netfilter_hook_drift;
 pub mod netlink_vs_proc;
+pub mod netns_utils;
+pub mod outbound_connections;
 pub mod overlay_lowerdir;
 pub mod overlayfs_whiteouts;
 pub mod ownerless_bpf_objects;
 pub mod ownerless_sockets;
+pub mod packet_sniffers;
 pub mod pam_nss;
 pub mod pins_non_bpffs;
+pub mod privileged_files;
+pub mod process_environ;
+pub mod process_masquerade;
+pub mod process_tree;
 pub mod scripts_d;
 pub mod seccomp_user_notify;
 pub mod sensitive_host_mounts;
 pub mod sensitive_kfunc;
+pub mod shell_startup;
 pub mod sockmap_sockhash;
 pub mod ssh_footholds;
 pub mod sudoers;
@@ -48,5 +65,8 @@ pub mod syscall_table;
 pub mod systemd_ghost;
 pub mod task_list_mismatch;
 pub mod task_snapshot;
+pub mod tracefs_inventory;
+pub mod udev_rules;
+pub mod unix_sockets;
 pub mod unknown_kprobes;
 pub mod xdp_tc_detached;
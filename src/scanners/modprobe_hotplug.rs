@@ -1,6 +1,9 @@
 use crate::ScanOutcome;
 use std::{fs, os::unix::fs::MetadataExt, path::Path};
 
+const REQUEST_KEY_CONF: &str = "/etc/request-key.conf";
+const REQUEST_KEY_DIR: &str = "/etc/request-key.d";
+
 pub fn run() -> ScanOutcome {
     let mut findings = Vec::new();
     let mut errors = Vec::new();
@@ -12,6 +15,21 @@ pub fn run() -> ScanOutcome {
         &mut findings,
         &mut errors,
     );
+    // Both files expose the same kernel variable; read it once.
+    let (helper_path, helper_label) = if Path::new("/sys/kernel/uevent_helper").exists() {
+        ("/sys/kernel/uevent_helper", "uevent_helper")
+    } else {
+        ("/proc/sys/kernel/hotplug", "hotplug")
+    };
+    analyze_proc_path(helper_path, None, helper_label, &mut findings, &mut errors);
+    analyze_proc_path(
+        "/proc/sys/kernel/poweroff_cmd",
+        Some("/sbin/poweroff"),
+        "poweroff_cmd",
+        &mut findings,
+        &mut errors,
+    );
+    analyze_request_key(&mut findings, &mut errors);
 
     if findings.is_empty() {
         if errors.is_empty() {
@@ -57,28 +75,17 @@ fn analyze_proc_path(
 
     let mut issues = Vec::new();
 
-    if let Some(default_path) = default {
-        if value != default_path {
-            issues.push("non_default");
-        }
-    }
-
-    if !value.starts_with('/') {
-        issues.push("non_absolute");
-    }
-
-    if value.starts_with("/tmp/")
-        || value.starts_with("/var/tmp/")
-        || value.starts_with("/dev/shm/")
-        || value.contains("(deleted)")
-    {
-        issues.push("suspicious_location");
+    match default {
+        Some(default_path) if value != default_path => issues.push("non_default"),
+        // Helpers without a default (uevent_helper, hotplug) are normally
+        // left empty; udev handles uevents on any current system.
+        None => issues.push("helper_configured"),
+        _ => {}
     }
 
-    match investigate_target(value) {
-        Ok(target_issues) => issues.extend(target_issues),
-        Err(err) => errors.push(format!("{label}: {err}")),
-    }
+    // poweroff_cmd is split into argv, so only the program is a path.
+    let program = value.split_whitespace().next().unwrap_or(value);
+    issues.extend(evaluate_helper(program, label, errors));
 
     if !issues.is_empty() {
         findings.push(format!(
@@ -89,7 +96,7 @@ fn analyze_proc_path(
     }
 }
 
-fn investigate_target(path: &str) -> Result<Vec<&'static str>, String> {
+pub fn investigate_target(path: &str) -> Result<Vec<&'static str>, String> {
     let mut issues = Vec::new();
     let target = Path::new(path);
 
@@ -124,6 +131,78 @@ fn investigate_target(path: &str) -> Result<Vec<&'static str>, String> {
     Ok(issues)
 }
 
+/// Location and trust checks shared by every helper path.
+pub fn evaluate_helper(program: &str, label: &str, errors: &mut Vec<String>) -> Vec<&'static str> {
+    let mut issues = Vec::new();
+
+    if !program.starts_with('/') {
+        issues.push("non_absolute");
+    }
+
+    if program.starts_with("/tmp/")
+        || program.starts_with("/var/tmp/")
+        || program.starts_with("/dev/shm/")
+        || program.contains("(deleted)")
+    {
+        issues.push("suspicious_location");
+    }
+
+    match investigate_target(program) {
+        Ok(target_issues) => issues.extend(target_issues),
+        Err(err) => errors.push(format!("{label}: {err}")),
+    }
+
+    issues
+}
+
+/// request-key(8) runs the program named in the fifth column as root.
+fn analyze_request_key(findings: &mut Vec<String>, errors: &mut Vec<String>) {
+    let mut files = vec![REQUEST_KEY_CONF.to_string()];
+    if let Ok(entries) = fs::read_dir(REQUEST_KEY_DIR) {
+        let mut extra: Vec<String> = entries
+            .flatten()
+            .map(|entry| entry.path().to_string_lossy().into_owned())
+            .filter(|path| path.ends_with(".conf"))
+            .collect();
+        extra.sort();
+        files.extend(extra);
+    }
+
+    for file in files {
+        let content = match fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(err) => {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    errors.push(format!("request-key: failed to read {file}: {err}"));
+                }
+                continue;
+            }
+        };
+        for (idx, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some(program) = line.split_whitespace().nth(4) else {
+                continue;
+            };
+            // "negate" is a built-in action rather than a program.
+            if program == "negate" {
+                continue;
+            }
+            let label = format!("request-key:{file}:{}", idx + 1);
+            let issues = evaluate_helper(program, &label, errors);
+            if !issues.is_empty() {
+                findings.push(format!(
+                    "{label} path={} issues={}",
+                    program,
+                    issues.join("|")
+                ));
+            }
+        }
+    }
+}
+
 fn looks_temporary(path: &Path) -> bool {
     if let Some(s) = path.to_str() {
         s.starts_with("/tmp/") || s.starts_with("/var/tmp/") || s.starts_with("/dev/shm/")
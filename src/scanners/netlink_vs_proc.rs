@@ -1,3 +1,4 @@
+use super::netns_utils::sweep_net_namespaces;
 use crate::ScanOutcome;
 use std::{
     collections::{BTreeMap, BTreeSet},
@@ -17,6 +18,24 @@ pub fn run() -> ScanOutcome {
         return Err("ss not available to query sockets".to_string());
     }
 
+    let (mut findings, errors) = sweep_net_namespaces(scan_namespace)?;
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+fn scan_namespace(proc_net: &str) -> (Vec<String>, Vec<String>) {
     let mut netlink_map = BTreeMap::new();
     let mut proc_map = BTreeMap::new();
     let mut errors = Vec::new();
@@ -26,7 +45,7 @@ pub fn run() -> ScanOutcome {
             Ok(map) => netlink_map.extend(map),
             Err(err) => errors.push(err),
         }
-        match collect_proc(proto) {
+        match collect_proc(proc_net, proto) {
             Ok(map) => proc_map.extend(map),
             Err(err) => errors.push(err),
         }
@@ -63,19 +82,7 @@ pub fn run() -> ScanOutcome {
         }
     }
 
-    if findings.is_empty() {
-        if errors.is_empty() {
-            Ok(None)
-        } else {
-            Err(errors.join(", "))
-        }
-    } else {
-        findings.sort();
-        if !errors.is_empty() {
-            findings.push(format!("collection_errors={}", errors.join(", ")));
-        }
-        Ok(Some(findings.join("\n")))
-    }
+    (findings, errors)
 }
 
 fn collect_ss(flag: &str, proto: &str) -> Result<BTreeMap<SocketKey, NetlinkEntry>, String> {
@@ -186,11 +193,11 @@ fn normalize_ss_endpoint(raw: &str) -> Option<String> {
     Some(format_endpoint(addr, port))
 }
 
-fn collect_proc(proto: &str) -> Result<BTreeMap<SocketKey, ProcEntry>, String> {
+fn collect_proc(proc_net: &str, proto: &str) -> Result<BTreeMap<SocketKey, ProcEntry>, String> {
     let mut map = BTreeMap::new();
     let files = [
-        format!("/proc/net/{proto}"),
-        format!("/proc/net/{}6", proto),
+        format!("{proc_net}/{proto}"),
+        format!("{proc_net}/{}6", proto),
     ];
 
     for path in files {
@@ -0,0 +1,216 @@
+use super::container_utils::collect_container_states;
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    os::{fd::AsRawFd, unix::fs::MetadataExt},
+    thread,
+};
+
+const NAMED_NETNS_DIR: &str = "/run/netns";
+const HOST_PROC_NET: &str = "/proc/net";
+// /proc/net follows the thread group leader; a thread that called setns sees
+// its own namespace only through thread-self.
+const THREAD_PROC_NET: &str = "/proc/thread-self/net";
+
+pub struct NetNamespace {
+    pub inum: u64,
+    pub current: bool,
+    pub owner: Option<(i32, String)>,
+    pub names: Vec<String>,
+    pub container: Option<String>,
+    handle: File,
+}
+
+impl NetNamespace {
+    /// Labels findings from foreign namespaces; the scanner's own namespace is
+    /// left untagged so its output reads as before.
+    pub fn tag(&self) -> Option<String> {
+        if self.current {
+            return None;
+        }
+        let mut segments = vec![format!("netns={}", self.inum)];
+        if let Some((pid, comm)) = &self.owner {
+            segments.push(format!("netns_owner={pid}:{comm}"));
+        }
+        if !self.names.is_empty() {
+            segments.push(format!("netns_path={}", self.names.join("|")));
+        }
+        if let Some(container) = &self.container {
+            segments.push(format!("container={container}"));
+        }
+        Some(segments.join(", "))
+    }
+
+    /// Runs `scan` with the namespace's view of /proc/net. Foreign namespaces
+    /// are entered on a short-lived thread so the caller's namespace is never
+    /// changed; commands spawned from that thread inherit the namespace.
+    pub fn enter<T, F>(&self, scan: F) -> Result<T, String>
+    where
+        T: Send,
+        F: FnOnce(&str) -> T + Send,
+    {
+        if self.current {
+            return Ok(scan(HOST_PROC_NET));
+        }
+        let fd = self.handle.as_raw_fd();
+        thread::scope(|scope| {
+            scope
+                .spawn(move || {
+                    if unsafe { libc::setns(fd, libc::CLONE_NEWNET) } != 0 {
+                        return Err(format!("setns: {}", std::io::Error::last_os_error()));
+                    }
+                    Ok(scan(THREAD_PROC_NET))
+                })
+                .join()
+                .map_err(|_| "namespace worker panicked".to_string())?
+        })
+    }
+}
+
+/// Enumerates every distinct network namespace reachable through process
+/// ns links and bind mounts under /run/netns.
+pub fn collect_net_namespaces() -> Result<(Vec<NetNamespace>, Vec<String>), String> {
+    let own = fs::metadata("/proc/self/ns/net")
+        .map_err(|err| format!("failed to stat /proc/self/ns/net: {err}"))?;
+    let (current, nsfs_dev) = (own.ino(), own.dev());
+
+    let mut namespaces: BTreeMap<u64, NetNamespace> = BTreeMap::new();
+    let mut errors = Vec::new();
+
+    let entries = fs::read_dir("/proc").map_err(|err| format!("failed to read /proc: {err}"))?;
+    let mut pids: Vec<i32> = entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+        .collect();
+    pids.sort_unstable();
+
+    for pid in pids {
+        let path = format!("/proc/{pid}/ns/net");
+        let Ok(meta) = fs::metadata(&path) else {
+            continue;
+        };
+        if namespaces.contains_key(&meta.ino()) {
+            continue;
+        }
+        let handle = match File::open(&path) {
+            Ok(handle) => handle,
+            Err(err) if matches!(err.raw_os_error(), Some(2) | Some(3)) => continue,
+            Err(err) => {
+                errors.push(format!("failed to open {path}: {err}"));
+                continue;
+            }
+        };
+        let comm = fs::read_to_string(format!("/proc/{pid}/comm"))
+            .unwrap_or_else(|_| "unknown".to_string())
+            .trim()
+            .to_string();
+        namespaces.insert(
+            meta.ino(),
+            NetNamespace {
+                inum: meta.ino(),
+                current: meta.ino() == current,
+                owner: Some((pid, comm)),
+                names: Vec::new(),
+                container: cgroup_container_id(pid),
+                handle,
+            },
+        );
+    }
+
+    if let Ok(entries) = fs::read_dir(NAMED_NETNS_DIR) {
+        for entry in entries.flatten() {
+            let path = entry.path().to_string_lossy().into_owned();
+            let meta = match fs::metadata(&path) {
+                Ok(meta) => meta,
+                Err(err) => {
+                    errors.push(format!("failed to stat {path}: {err}"));
+                    continue;
+                }
+            };
+            if let Some(existing) = namespaces.get_mut(&meta.ino()) {
+                existing.names.push(path);
+                continue;
+            }
+            // A placeholder that was never bind-mounted lives on tmpfs, not nsfs.
+            if meta.dev() != nsfs_dev {
+                continue;
+            }
+            let handle = match File::open(&path) {
+                Ok(handle) => handle,
+                Err(err) => {
+                    errors.push(format!("failed to open {path}: {err}"));
+                    continue;
+                }
+            };
+            namespaces.insert(
+                meta.ino(),
+                NetNamespace {
+                    inum: meta.ino(),
+                    current: meta.ino() == current,
+                    owner: None,
+                    names: vec![path],
+                    container: None,
+                    handle,
+                },
+            );
+        }
+    }
+
+    let inventory = collect_container_states(1024);
+    errors.extend(inventory.errors);
+    for state in inventory.states {
+        let Some(pid) = state.pid else {
+            continue;
+        };
+        let Ok(meta) = fs::metadata(format!("/proc/{pid}/ns/net")) else {
+            continue;
+        };
+        match namespaces.get_mut(&meta.ino()) {
+            Some(namespace) if !namespace.current => namespace.container = Some(state.id),
+            _ => {}
+        }
+    }
+
+    Ok((namespaces.into_values().collect(), errors))
+}
+
+/// Runs a per-namespace scan everywhere and merges the results, prefixing
+/// lines from foreign namespaces with their tag.
+pub fn sweep_net_namespaces<F>(scan: F) -> Result<(Vec<String>, Vec<String>), String>
+where
+    F: Fn(&str) -> (Vec<String>, Vec<String>) + Sync,
+{
+    let (namespaces, mut errors) = collect_net_namespaces()?;
+    let mut findings = Vec::new();
+
+    for namespace in &namespaces {
+        match (namespace.enter(&scan), namespace.tag()) {
+            (Ok((found, failed)), Some(tag)) => {
+                findings.extend(found.into_iter().map(|line| format!("{tag}, {line}")));
+                errors.extend(failed.into_iter().map(|err| format!("{tag}: {err}")));
+            }
+            (Ok((found, failed)), None) => {
+                findings.extend(found);
+                errors.extend(failed);
+            }
+            (Err(err), _) => errors.push(format!("netns={}: {err}", namespace.inum)),
+        }
+    }
+
+    Ok((findings, errors))
+}
+
+/// Pulls a runtime container id out of cgroup paths such as
+/// `/docker/<id>`, `docker-<id>.scope`, `crio-<id>.scope` or `libpod-<id>`.
+fn cgroup_container_id(pid: i32) -> Option<String> {
+    let content = fs::read_to_string(format!("/proc/{pid}/cgroup")).ok()?;
+    content
+        .lines()
+        .filter_map(|line| line.splitn(3, ':').nth(2))
+        .flat_map(|path| path.split('/'))
+        .find_map(|segment| {
+            let id = segment.trim_end_matches(".scope");
+            let id = id.rsplit('-').next().unwrap_or(id);
+            (id.len() == 64 && id.bytes().all(|b| b.is_ascii_hexdigit())).then(|| id.to_string())
+        })
+}
@@ -0,0 +1,309 @@
+use super::{
+    hidden_listeners::{format_endpoint, parse_proc_address},
+    netns_utils::collect_net_namespaces,
+    ownerless_sockets::collect_owners,
+};
+use crate::ScanOutcome;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs,
+    net::IpAddr,
+};
+
+const TMP_PREFIXES: &[&str] = &["/tmp/", "/var/tmp/", "/dev/shm/", "/run/shm/"];
+
+// Daemons that have no business opening connections to the outside world.
+const NO_EGRESS_DAEMONS: &[&str] = &[
+    "cron",
+    "crond",
+    "atd",
+    "dbus-daemon",
+    "polkitd",
+    "systemd-logind",
+    "systemd-udevd",
+    "udevd",
+    "systemd-journal",
+    "auditd",
+    "agetty",
+    "login",
+    "getty",
+    "acpid",
+    "irqbalance",
+    "rtkit-daemon",
+];
+
+const STANDARD_PORTS: &[u16] = &[
+    22, 25, 53, 80, 123, 443, 465, 587, 853, 993, 995, 3128, 5222, 6443, 8080, 8443, 9418, 11371,
+];
+
+// Programs expected to hold raw or packet sockets.
+pub const RAW_SOCKET_OWNERS: &[&str] = &[
+    "dhclient",
+    "dhcpcd",
+    "dhcpd",
+    "NetworkManager",
+    "systemd-network",
+    "wpa_supplicant",
+    "ping",
+    "ping6",
+    "arping",
+    "traceroute",
+    "mtr",
+    "mtr-packet",
+    "tcpdump",
+    "dumpcap",
+    "tshark",
+    "wireshark",
+    "keepalived",
+    "lldpd",
+    "suricata",
+    "snort",
+    "zeek",
+    "bird",
+    "ospfd",
+    "pimd",
+    "radvd",
+    "nmap",
+];
+
+struct Connection {
+    proto: &'static str,
+    local: String,
+    local_port: u16,
+    remote: String,
+    remote_ip: IpAddr,
+    remote_port: u16,
+    state: &'static str,
+}
+
+type ListeningPorts = HashSet<(&'static str, u16)>;
+
+struct Owner {
+    comm: String,
+    exe: String,
+    tty: bool,
+}
+
+pub fn run() -> ScanOutcome {
+    let mut owners: BTreeMap<String, BTreeSet<i32>> = BTreeMap::new();
+    collect_owners("/proc", &mut owners)
+        .map_err(|err| format!("failed to enumerate fd owners: {err}"))?;
+
+    let (namespaces, mut errors) = collect_net_namespaces()?;
+    let mut findings = Vec::new();
+    let mut owner_cache: BTreeMap<i32, Option<Owner>> = BTreeMap::new();
+
+    for namespace in &namespaces {
+        let scanned = namespace
+            .enter(|proc_net| (collect_connections(proc_net), raw_socket_inodes(proc_net)));
+        let (connections, raw_sockets) = match scanned {
+            Ok(found) => found,
+            Err(err) => {
+                errors.push(format!("netns={}: {err}", namespace.inum));
+                continue;
+            }
+        };
+        let prefix = namespace
+            .tag()
+            .map(|tag| format!("{tag}, "))
+            .unwrap_or_default();
+
+        for (inode, conn) in &connections {
+            if conn.remote_ip.is_loopback() || is_mapped_loopback(&conn.remote_ip) {
+                continue;
+            }
+
+            for pid in owners.get(inode).into_iter().flatten() {
+                let Some(owner) = owner_cache.entry(*pid).or_insert_with(|| read_owner(*pid))
+                else {
+                    continue;
+                };
+
+                let mut issues = Vec::new();
+                if NO_EGRESS_DAEMONS.contains(&owner.comm.as_str()) {
+                    issues.push("daemon_egress".to_string());
+                }
+                if !owner.tty && !STANDARD_PORTS.contains(&conn.remote_port) {
+                    issues.push(format!("nonstandard_port_no_tty({})", conn.remote_port));
+                }
+                if let Some(origin) = volatile_origin(&owner.exe) {
+                    issues.push(format!("beacon_from_{origin}"));
+                }
+                if issues.is_empty() {
+                    continue;
+                }
+
+                findings.push(format!(
+                    "{}pid={}, comm={}, exe={}, proto={}, local={}, remote={}, state={}, issues={}",
+                    prefix,
+                    pid,
+                    owner.comm,
+                    owner.exe,
+                    conn.proto,
+                    conn.local,
+                    conn.remote,
+                    conn.state,
+                    issues.join(",")
+                ));
+            }
+        }
+
+        for (inode, kind) in raw_sockets {
+            for pid in owners.get(&inode).into_iter().flatten() {
+                let Some(owner) = owner_cache.entry(*pid).or_insert_with(|| read_owner(*pid))
+                else {
+                    continue;
+                };
+                let exe_base = owner
+                    .exe
+                    .trim_end_matches(" (deleted)")
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or("");
+                if RAW_SOCKET_OWNERS.contains(&owner.comm.as_str())
+                    || RAW_SOCKET_OWNERS.contains(&exe_base)
+                {
+                    continue;
+                }
+                findings.push(format!(
+                    "{}pid={}, comm={}, exe={}, socket={}, inode={}, issues=raw_socket_unexpected_owner",
+                    prefix, pid, owner.comm, owner.exe, kind, inode
+                ));
+            }
+        }
+    }
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        findings.dedup();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+/// Outbound connections in one namespace, minus those accepted on a local
+/// listener.
+fn collect_connections(proc_net: &str) -> BTreeMap<String, Connection> {
+    let mut connections = BTreeMap::new();
+    let mut listening: ListeningPorts = HashSet::new();
+
+    for (name, proto, ipv6) in [
+        ("tcp", "tcp", false),
+        ("tcp6", "tcp", true),
+        ("udp", "udp", false),
+        ("udp6", "udp", true),
+    ] {
+        let Ok(content) = fs::read_to_string(format!("{proc_net}/{name}")) else {
+            continue;
+        };
+        for line in content.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 || fields[9] == "0" {
+                continue;
+            }
+            let (Some((local_ip, local_port)), Some((remote_ip, remote_port))) = (
+                parse_proc_address(fields[1], ipv6),
+                parse_proc_address(fields[2], ipv6),
+            ) else {
+                continue;
+            };
+            let state = match (proto, fields[3]) {
+                ("tcp", "0A") => {
+                    listening.insert((proto, local_port));
+                    continue;
+                }
+                // UDP reports 07 for every unconnected socket; only a bound
+                // port with no peer acts as a server.
+                ("udp", "07") => {
+                    if remote_ip.is_unspecified() && remote_port == 0 && local_port != 0 {
+                        listening.insert((proto, local_port));
+                    }
+                    continue;
+                }
+                (_, "01") => "established",
+                ("tcp", "02") => "syn_sent",
+                _ => continue,
+            };
+            connections.insert(
+                fields[9].to_string(),
+                Connection {
+                    proto,
+                    local: format_endpoint(local_ip, local_port),
+                    local_port,
+                    remote: format_endpoint(remote_ip, remote_port),
+                    remote_ip,
+                    remote_port,
+                    state,
+                },
+            );
+        }
+    }
+
+    connections.retain(|_, conn| !listening.contains(&(conn.proto, conn.local_port)));
+    connections
+}
+
+fn raw_socket_inodes(proc_net: &str) -> Vec<(String, &'static str)> {
+    let mut inodes = Vec::new();
+    for (path, kind, column) in [
+        ("raw", "raw", 9),
+        ("raw6", "raw6", 9),
+        ("packet", "packet", 8),
+    ] {
+        let Ok(content) = fs::read_to_string(format!("{proc_net}/{path}")) else {
+            continue;
+        };
+        for line in content.lines().skip(1) {
+            match line.split_whitespace().nth(column) {
+                Some(inode) if inode != "0" => inodes.push((inode.to_string(), kind)),
+                _ => {}
+            }
+        }
+    }
+    inodes
+}
+
+fn read_owner(pid: i32) -> Option<Owner> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    let (_, tail) = stat.rsplit_once(')')?;
+    let tty_nr: i64 = tail.split_whitespace().nth(4)?.parse().ok()?;
+    let comm = fs::read_to_string(format!("/proc/{pid}/comm"))
+        .unwrap_or_else(|_| "unknown".to_string())
+        .trim()
+        .to_string();
+    let exe = fs::read_link(format!("/proc/{pid}/exe"))
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| "unknown".to_string());
+    Some(Owner {
+        comm,
+        exe,
+        tty: tty_nr != 0,
+    })
+}
+
+fn volatile_origin(exe: &str) -> Option<&'static str> {
+    if exe.contains("memfd:") {
+        Some("memfd")
+    } else if exe.ends_with(" (deleted)") {
+        Some("deleted")
+    } else if TMP_PREFIXES.iter().any(|prefix| exe.starts_with(prefix)) {
+        Some("tmp")
+    } else {
+        None
+    }
+}
+
+fn is_mapped_loopback(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().is_some_and(|v4| v4.is_loopback()),
+        IpAddr::V4(_) => false,
+    }
+}
@@ -1,3 +1,4 @@
+use super::netns_utils::sweep_net_namespaces;
 use crate::ScanOutcome;
 use std::{
     collections::{BTreeMap, BTreeSet},
@@ -6,36 +7,66 @@ use std::{
 };
 
 pub fn run() -> ScanOutcome {
+    let mut inode_to_owners: BTreeMap<String, BTreeSet<i32>> = BTreeMap::new();
+
+    if let Err(err) = collect_owners("/proc", &mut inode_to_owners) {
+        return Err(format!("failed to enumerate fd owners: {err}"));
+    }
+
+    // Socket inodes are global, so one fd walk covers every namespace.
+    let (mut findings, errors) =
+        sweep_net_namespaces(|proc_net| scan_namespace(proc_net, &inode_to_owners))?;
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+fn scan_namespace(
+    proc_net: &str,
+    inode_to_owners: &BTreeMap<String, BTreeSet<i32>>,
+) -> (Vec<String>, Vec<String>) {
     let mut inode_to_socket = BTreeMap::new();
 
-    for path in [
-        "/proc/net/tcp",
-        "/proc/net/tcp6",
-        "/proc/net/udp",
-        "/proc/net/udp6",
-    ] {
-        if let Ok(content) = fs::read_to_string(path) {
+    for name in ["tcp", "tcp6", "udp", "udp6"] {
+        if let Ok(content) = fs::read_to_string(format!("{proc_net}/{name}")) {
             for line in content.lines().skip(1) {
                 if let Some(inode) = line.split_whitespace().nth(9) {
-                    inode_to_socket.insert(inode.to_string(), path.to_string());
+                    inode_to_socket.insert(inode.to_string(), format!("/proc/net/{name}"));
                 }
             }
         }
     }
 
-    let mut inode_to_owners: BTreeMap<String, BTreeSet<i32>> = BTreeMap::new();
+    inode_to_socket.retain(|inode, _| inode != "0" && !inode_to_owners.contains_key(inode));
+    if inode_to_socket.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
 
-    if let Err(err) = collect_owners("/proc", &mut inode_to_owners) {
-        return Err(format!("failed to enumerate fd owners: {err}"));
+    // Sockets opened after the first fd walk look ownerless; walk again now
+    // that this namespace's tables have been read.
+    let mut current_owners = BTreeMap::new();
+    if let Err(err) = collect_owners("/proc", &mut current_owners) {
+        return (
+            Vec::new(),
+            vec![format!("failed to re-check fd owners: {err}")],
+        );
     }
 
     let mut findings = Vec::new();
 
     for (inode, source) in inode_to_socket {
-        if inode == "0" {
-            continue;
-        }
-        if !inode_to_owners.contains_key(&inode) {
+        if !current_owners.contains_key(&inode) {
             findings.push(format!(
                 "proto_source={}, inode={}, owner_pids=∅",
                 source, inode
@@ -43,15 +74,10 @@ pub fn run() -> ScanOutcome {
         }
     }
 
-    if findings.is_empty() {
-        Ok(None)
-    } else {
-        findings.sort();
-        Ok(Some(findings.join("\n")))
-    }
+    (findings, Vec::new())
 }
 
-fn collect_owners(dir: &str, map: &mut BTreeMap<String, BTreeSet<i32>>) -> Result<(), String> {
+pub fn collect_owners(dir: &str, map: &mut BTreeMap<String, BTreeSet<i32>>) -> Result<(), String> {
     for entry in fs::read_dir(dir).map_err(|err| format!("failed to read {dir}: {err}"))? {
         let entry = entry.map_err(|err| format!("failed to iterate {dir}: {err}"))?;
         let file_type = entry
@@ -0,0 +1,421 @@
+use super::outbound_connections::RAW_SOCKET_OWNERS;
+use crate::ScanOutcome;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+};
+
+const TMP_PREFIXES: &[&str] = &["/tmp/", "/var/tmp/", "/dev/shm/", "/run/shm/"];
+const MAX_LISTED_INSNS: usize = 64;
+const SOCK_RAW: &str = "3";
+const ETH_P_IPV6: &str = "86dd";
+const ETHERNET_HEADER_LEN: u32 = 14;
+const IPV4_HEADER_LEN: u32 = 20;
+const IPV6_HEADER_LEN: u32 = 40;
+const UDP_HEADER_LEN: u32 = 8;
+const TCP_HEADER_LEN: u32 = 20;
+const SKF_AD_OFF: u32 = 0xffff_f000;
+
+struct RawSocket {
+    kind: &'static str,
+    detail: String,
+    /// Bytes the filter sees before the network header: the Ethernet header
+    /// on SOCK_RAW packet sockets, nothing on SOCK_DGRAM and raw IP sockets.
+    link_len: u32,
+    /// Minimal network header in front of the transport header; IPv6 raw
+    /// sockets never see theirs.
+    network_len: u32,
+}
+
+impl RawSocket {
+    /// Absolute loads at or past this offset read beyond a minimal UDP
+    /// header into the payload.
+    fn payload_offset(&self) -> u32 {
+        self.link_len + self.network_len + UDP_HEADER_LEN
+    }
+
+    /// Indirect loads are relative to X, which filters set to the IP header
+    /// length; past a minimal TCP header they read the payload.
+    fn indirect_payload_offset(&self) -> u32 {
+        self.link_len + TCP_HEADER_LEN
+    }
+}
+
+pub fn run() -> ScanOutcome {
+    let sockets = collect_sockets();
+    if sockets.is_empty() {
+        return Ok(None);
+    }
+
+    let mut findings = Vec::new();
+    let mut errors = Vec::new();
+    let mut owned = BTreeSet::new();
+
+    let proc_dir = fs::read_dir("/proc").map_err(|err| format!("failed to read /proc: {err}"))?;
+    for entry in proc_dir.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<i32>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(format!("/proc/{pid}/fd")) else {
+            continue;
+        };
+        for fd_entry in fds.flatten() {
+            let Ok(target) = fs::read_link(fd_entry.path()) else {
+                continue;
+            };
+            let target = target.to_string_lossy();
+            let Some(inode) = target
+                .strip_prefix("socket:[")
+                .and_then(|rest| rest.strip_suffix(']'))
+            else {
+                continue;
+            };
+            let Some(socket) = sockets.get(inode) else {
+                continue;
+            };
+            owned.insert(inode.to_string());
+            let Some(fd) = fd_entry.file_name().to_str().and_then(|s| s.parse().ok()) else {
+                continue;
+            };
+            match inspect_socket(pid, fd, inode, socket) {
+                Ok(Some(record)) => findings.push(record),
+                Ok(None) => {}
+                Err(err) => errors.push(format!("pid={pid}: {err}")),
+            }
+        }
+    }
+
+    for (inode, socket) in &sockets {
+        if !owned.contains(inode) {
+            findings.push(format!(
+                "owner=∅, socket={}({}), inode={}, issues=no_owning_fd",
+                socket.kind, socket.detail, inode
+            ));
+        }
+    }
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+fn collect_sockets() -> BTreeMap<String, RawSocket> {
+    let mut sockets = BTreeMap::new();
+
+    if let Ok(content) = fs::read_to_string("/proc/net/packet") {
+        // sk RefCnt Type Proto Iface R Rmem User Inode
+        for line in content.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 9 || fields[8] == "0" {
+                continue;
+            }
+            sockets.insert(
+                fields[8].to_string(),
+                RawSocket {
+                    kind: "packet",
+                    detail: format!(
+                        "type={},proto=0x{},ifindex={}",
+                        fields[2], fields[3], fields[4]
+                    ),
+                    link_len: if fields[2] == SOCK_RAW {
+                        ETHERNET_HEADER_LEN
+                    } else {
+                        0
+                    },
+                    network_len: if fields[3].eq_ignore_ascii_case(ETH_P_IPV6) {
+                        IPV6_HEADER_LEN
+                    } else {
+                        IPV4_HEADER_LEN
+                    },
+                },
+            );
+        }
+    }
+
+    for (path, kind, network_len) in [
+        ("/proc/net/raw", "raw", IPV4_HEADER_LEN),
+        ("/proc/net/raw6", "raw6", 0),
+    ] {
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        for line in content.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 || fields[9] == "0" {
+                continue;
+            }
+            // The "port" of a raw socket's local address is its IP protocol.
+            let protocol = fields[1]
+                .rsplit(':')
+                .next()
+                .and_then(|hex| u16::from_str_radix(hex, 16).ok())
+                .unwrap_or(0);
+            sockets.insert(
+                fields[9].to_string(),
+                RawSocket {
+                    kind,
+                    detail: format!("ipproto={protocol}"),
+                    link_len: 0,
+                    network_len,
+                },
+            );
+        }
+    }
+
+    sockets
+}
+
+fn inspect_socket(
+    pid: i32,
+    fd: i32,
+    inode: &str,
+    socket: &RawSocket,
+) -> Result<Option<String>, String> {
+    let comm = fs::read_to_string(format!("/proc/{pid}/comm"))
+        .unwrap_or_else(|_| "unknown".to_string())
+        .trim()
+        .to_string();
+    let exe = fs::read_link(format!("/proc/{pid}/exe"))
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| "unknown".to_string());
+    let exe_base = exe
+        .trim_end_matches(" (deleted)")
+        .rsplit('/')
+        .next()
+        .unwrap_or("")
+        .to_string();
+
+    let mut issues = Vec::new();
+    let expected_owner = RAW_SOCKET_OWNERS.contains(&comm.as_str())
+        || RAW_SOCKET_OWNERS.contains(&exe_base.as_str());
+    if !expected_owner {
+        issues.push("unexpected_owner".to_string());
+    }
+    if exe.contains("memfd:")
+        || exe.ends_with(" (deleted)")
+        || TMP_PREFIXES.iter().any(|prefix| exe.starts_with(prefix))
+    {
+        issues.push("volatile_exe".to_string());
+    }
+
+    let mut record = format!(
+        "pid={}, comm={}, exe={}, socket={}({}), inode={}, fd={}",
+        pid, comm, exe, socket.kind, socket.detail, inode, fd
+    );
+
+    match fetch_filter(pid, fd) {
+        Ok(None) => record.push_str(", filter=none"),
+        Ok(Some(program)) => {
+            let (listing, matches, payload) = disassemble(&program, socket);
+            record.push_str(&format!(", filter_insns={}", program.len()));
+            if !matches.is_empty() {
+                record.push_str(&format!(", matches={}", matches.join("|")));
+            }
+            if payload && !expected_owner {
+                issues.push("payload_magic_filter".to_string());
+            }
+            if program.len() <= MAX_LISTED_INSNS {
+                record.push_str(&format!(", program={}", listing.join("; ")));
+            }
+        }
+        Err(err) => record.push_str(&format!(", filter=unreadable({err})")),
+    }
+
+    if issues.is_empty() {
+        return Ok(None);
+    }
+    record.push_str(&format!(", issues={}", issues.join(",")));
+    Ok(Some(record))
+}
+
+/// Duplicates the target's socket with pidfd_getfd and reads back the classic
+/// BPF program installed via SO_ATTACH_FILTER.
+fn fetch_filter(pid: i32, fd: i32) -> Result<Option<Vec<libc::sock_filter>>, String> {
+    let pidfd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid, 0) };
+    if pidfd < 0 {
+        return Err(format!("pidfd_open: {}", std::io::Error::last_os_error()));
+    }
+    let pidfd = unsafe { OwnedFd::from_raw_fd(pidfd as i32) };
+
+    let local = unsafe { libc::syscall(libc::SYS_pidfd_getfd, pidfd.as_raw_fd(), fd, 0) };
+    if local < 0 {
+        return Err(format!("pidfd_getfd: {}", std::io::Error::last_os_error()));
+    }
+    let local = unsafe { OwnedFd::from_raw_fd(local as i32) };
+
+    // With a zero length the kernel reports the number of instructions.
+    let mut count: libc::socklen_t = 0;
+    let ret = unsafe {
+        libc::getsockopt(
+            local.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_GET_FILTER,
+            std::ptr::null_mut(),
+            &mut count,
+        )
+    };
+    if ret < 0 {
+        return Err(format!(
+            "SO_GET_FILTER: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    if count == 0 {
+        return Ok(None);
+    }
+
+    let mut program = vec![
+        libc::sock_filter {
+            code: 0,
+            jt: 0,
+            jf: 0,
+            k: 0,
+        };
+        count as usize
+    ];
+    let mut len = count;
+    let ret = unsafe {
+        libc::getsockopt(
+            local.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_GET_FILTER,
+            program.as_mut_ptr() as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if ret < 0 {
+        return Err(format!(
+            "SO_GET_FILTER: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    program.truncate((len as usize).min(program.len()));
+    Ok(Some(program))
+}
+
+/// Renders the program in tcpdump -d style and extracts the constants it
+/// compares loaded packet fields against.
+fn disassemble(
+    program: &[libc::sock_filter],
+    socket: &RawSocket,
+) -> (Vec<String>, Vec<String>, bool) {
+    let mut listing = Vec::new();
+    let mut matches = Vec::new();
+    let mut payload = false;
+    let mut loaded = String::from("A");
+    let mut loaded_payload = false;
+
+    for (idx, insn) in program.iter().enumerate() {
+        let code = insn.code;
+        let k = insn.k;
+        let size = match code & 0x18 {
+            0x00 => "",
+            0x08 => "h",
+            _ => "b",
+        };
+        let text = match code & 0x07 {
+            0x00 | 0x01 => {
+                let reg = if code & 0x07 == 0x00 { "ld" } else { "ldx" };
+                let operand = match code & 0xe0 {
+                    0x00 => format!("#{k:#x}"),
+                    0x20 if k >= SKF_AD_OFF => format!("#ancillary({})", k - SKF_AD_OFF),
+                    0x20 => format!("[{k}]"),
+                    0x40 => format!("[x + {k}]"),
+                    0x60 => format!("M[{k}]"),
+                    0x80 => "#pktlen".to_string(),
+                    0xa0 => format!("4*([{k}]&0xf)"),
+                    _ => format!("?{k:#x}"),
+                };
+                if code & 0x07 == 0x00 {
+                    loaded = format!("ld{size} {operand}");
+                    loaded_payload = match code & 0xe0 {
+                        0x20 => (socket.payload_offset()..SKF_AD_OFF).contains(&k),
+                        0x40 => (socket.indirect_payload_offset()..SKF_AD_OFF).contains(&k),
+                        _ => false,
+                    };
+                }
+                format!("{reg}{size} {operand}")
+            }
+            0x02 => format!("st M[{k}]"),
+            0x03 => format!("stx M[{k}]"),
+            0x04 => {
+                let op = match code & 0xf0 {
+                    0x00 => "add",
+                    0x10 => "sub",
+                    0x20 => "mul",
+                    0x30 => "div",
+                    0x40 => "or",
+                    0x50 => "and",
+                    0x60 => "lsh",
+                    0x70 => "rsh",
+                    0x80 => "neg",
+                    0x90 => "mod",
+                    0xa0 => "xor",
+                    _ => "alu?",
+                };
+                if code & 0x08 == 0 {
+                    format!("{op} #{k:#x}")
+                } else {
+                    format!("{op} x")
+                }
+            }
+            0x05 => {
+                let target_t = idx + 1 + insn.jt as usize;
+                let target_f = idx + 1 + insn.jf as usize;
+                let op = match code & 0xf0 {
+                    0x00 => format!("ja {}", idx + 1 + k as usize),
+                    0x10 => "jeq".to_string(),
+                    0x20 => "jgt".to_string(),
+                    0x30 => "jge".to_string(),
+                    0x40 => "jset".to_string(),
+                    _ => "jmp?".to_string(),
+                };
+                if code & 0xf0 == 0x00 {
+                    op
+                } else if code & 0x08 == 0 {
+                    let symbol = match code & 0xf0 {
+                        0x10 => "==",
+                        0x20 => ">",
+                        0x30 => ">=",
+                        _ => "&",
+                    };
+                    matches.push(format!("{loaded}{symbol}{k:#x}"));
+                    if loaded_payload {
+                        payload = true;
+                    }
+                    format!("{op} #{k:#x} jt {target_t} jf {target_f}")
+                } else {
+                    format!("{op} x jt {target_t} jf {target_f}")
+                }
+            }
+            0x06 => match code & 0x18 {
+                0x08 => "ret x".to_string(),
+                0x10 => "ret a".to_string(),
+                _ => format!("ret #{k}"),
+            },
+            _ => match code & 0xf8 {
+                0x00 => "tax".to_string(),
+                0x80 => "txa".to_string(),
+                _ => format!("misc?{code:#x}"),
+            },
+        };
+        listing.push(format!("({idx:03}) {text}"));
+    }
+
+    (listing, matches, payload)
+}
@@ -0,0 +1,377 @@
+use crate::ScanOutcome;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    ffi::CString,
+    fs,
+    os::unix::{ffi::OsStrExt, fs::MetadataExt},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// Staging areas go first so the sweep limits never starve them.
+const ROOTS: &[&str] = &[
+    "/tmp", "/var/tmp", "/dev/shm", "/usr", "/bin", "/sbin", "/lib", "/lib64", "/opt", "/etc",
+    "/home", "/root", "/srv", "/var", "/boot",
+];
+const SKIP_DIRS: &[&str] = &["/var/lib/docker/overlay2", "/var/lib/containers/storage"];
+const SYSTEM_PREFIXES: &[&str] = &["/usr/", "/bin/", "/sbin/", "/lib/", "/lib64/"];
+const DIR_LIMIT: usize = 50000;
+const FILE_LIMIT: usize = 500000;
+const MAX_DEPTH: usize = 16;
+const RECENT_WINDOW_SECS: i64 = 7 * 86400;
+
+const CAP_NAMES: &[&str] = &[
+    "cap_chown",
+    "cap_dac_override",
+    "cap_dac_read_search",
+    "cap_fowner",
+    "cap_fsetid",
+    "cap_kill",
+    "cap_setgid",
+    "cap_setuid",
+    "cap_setpcap",
+    "cap_linux_immutable",
+    "cap_net_bind_service",
+    "cap_net_broadcast",
+    "cap_net_admin",
+    "cap_net_raw",
+    "cap_ipc_lock",
+    "cap_ipc_owner",
+    "cap_sys_module",
+    "cap_sys_rawio",
+    "cap_sys_chroot",
+    "cap_sys_ptrace",
+    "cap_sys_pacct",
+    "cap_sys_admin",
+    "cap_sys_boot",
+    "cap_sys_nice",
+    "cap_sys_resource",
+    "cap_sys_time",
+    "cap_sys_tty_config",
+    "cap_mknod",
+    "cap_lease",
+    "cap_audit_write",
+    "cap_audit_control",
+    "cap_setfcap",
+    "cap_mac_override",
+    "cap_mac_admin",
+    "cap_syslog",
+    "cap_wake_alarm",
+    "cap_block_suspend",
+    "cap_audit_read",
+    "cap_perfmon",
+    "cap_bpf",
+    "cap_checkpoint_restore",
+];
+
+const DANGEROUS_CAPS: &[&str] = &[
+    "cap_sys_admin",
+    "cap_dac_override",
+    "cap_dac_read_search",
+    "cap_setuid",
+    "cap_setgid",
+    "cap_fowner",
+    "cap_chown",
+    "cap_sys_module",
+    "cap_sys_ptrace",
+    "cap_sys_rawio",
+    "cap_setfcap",
+    "cap_bpf",
+];
+
+#[derive(Default)]
+struct SweepStats {
+    dirs: usize,
+    files: usize,
+    suid: usize,
+    sgid: usize,
+    caps: usize,
+    truncated: bool,
+}
+
+pub fn run() -> ScanOutcome {
+    let mut findings = Vec::new();
+    let mut errors = Vec::new();
+    let mut stats = SweepStats::default();
+
+    let owned = load_dpkg_owned_paths();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let mut dir_cache = HashMap::new();
+    let mut seen_roots: Vec<PathBuf> = Vec::new();
+
+    for root in ROOTS {
+        let root = Path::new(root);
+        // /bin and friends are often symlinks into /usr.
+        let Ok(canonical) = fs::canonicalize(root) else {
+            continue;
+        };
+        if seen_roots.iter().any(|seen| canonical.starts_with(seen)) {
+            continue;
+        }
+        seen_roots.push(canonical.clone());
+        sweep(
+            &canonical,
+            &owned,
+            now,
+            &mut dir_cache,
+            &mut stats,
+            &mut findings,
+            &mut errors,
+        );
+    }
+
+    if stats.truncated {
+        errors.push(format!(
+            "sweep truncated after {} dirs/{} files",
+            stats.dirs, stats.files
+        ));
+    }
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        findings.push(format!(
+            "inventory suid={}, sgid={}, file_caps={}, dirs_scanned={}, package_db={}",
+            stats.suid,
+            stats.sgid,
+            stats.caps,
+            stats.dirs,
+            if owned.is_some() { "dpkg" } else { "none" }
+        ));
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+fn sweep(
+    root: &Path,
+    owned: &Option<HashSet<PathBuf>>,
+    now: i64,
+    dir_cache: &mut HashMap<PathBuf, bool>,
+    stats: &mut SweepStats,
+    findings: &mut Vec<String>,
+    errors: &mut Vec<String>,
+) {
+    let root_dev = match fs::symlink_metadata(root) {
+        Ok(meta) => meta.dev(),
+        Err(err) => {
+            errors.push(format!("failed to stat {}: {err}", root.display()));
+            return;
+        }
+    };
+
+    let mut queue = VecDeque::new();
+    queue.push_back((root.to_path_buf(), 0usize));
+
+    while let Some((dir, depth)) = queue.pop_front() {
+        if stats.dirs >= DIR_LIMIT || stats.files >= FILE_LIMIT {
+            stats.truncated = true;
+            return;
+        }
+        stats.dirs += 1;
+
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) => {
+                if matches!(err.raw_os_error(), Some(libc::EACCES) | Some(libc::ENOENT)) {
+                    continue;
+                }
+                errors.push(format!("failed to read {}: {err}", dir.display()));
+                continue;
+            }
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(meta) = fs::symlink_metadata(&path) else {
+                continue;
+            };
+            let file_type = meta.file_type();
+
+            if file_type.is_dir() {
+                if depth + 1 < MAX_DEPTH
+                    && meta.dev() == root_dev
+                    && !SKIP_DIRS.iter().any(|skip| path == Path::new(skip))
+                    && !ROOTS.iter().any(|other| path == Path::new(other))
+                {
+                    queue.push_back((path, depth + 1));
+                }
+                continue;
+            }
+            if !file_type.is_file() {
+                continue;
+            }
+            stats.files += 1;
+
+            let mode = meta.mode();
+            let setuid = mode & 0o4000 != 0;
+            let setgid = mode & 0o2000 != 0 && mode & 0o010 != 0;
+            let caps = read_file_caps(&path);
+
+            if !setuid && !setgid && caps.is_none() {
+                continue;
+            }
+            if setuid {
+                stats.suid += 1;
+            }
+            if setgid {
+                stats.sgid += 1;
+            }
+            if caps.is_some() {
+                stats.caps += 1;
+            }
+
+            let mut issues = Vec::new();
+            let path_str = path.to_string_lossy();
+
+            match owned {
+                Some(set) if !set.contains(&path) => issues.push("not_package_owned".to_string()),
+                None if !SYSTEM_PREFIXES.iter().any(|p| path_str.starts_with(p)) => {
+                    issues.push("outside_system_paths".to_string())
+                }
+                _ => {}
+            }
+            if meta.uid() != 0 {
+                issues.push(format!("owner_uid={}", meta.uid()));
+            }
+            if mode & 0o022 != 0 {
+                issues.push("file_writable".to_string());
+            }
+            if let Some(parent) = path.parent() {
+                let writable = *dir_cache
+                    .entry(parent.to_path_buf())
+                    .or_insert_with(|| directory_writable(parent));
+                if writable {
+                    issues.push("writable_dir".to_string());
+                }
+            }
+            if now - meta.ctime() < RECENT_WINDOW_SECS || now - meta.mtime() < RECENT_WINDOW_SECS {
+                issues.push("recently_changed".to_string());
+            }
+
+            let mut cap_summary = String::new();
+            if let Some((names, effective)) = &caps {
+                let dangerous: Vec<&str> = names
+                    .iter()
+                    .copied()
+                    .filter(|name| DANGEROUS_CAPS.contains(name))
+                    .collect();
+                if !dangerous.is_empty() {
+                    issues.push(format!("dangerous_caps={}", dangerous.join("|")));
+                }
+                cap_summary = format!(
+                    ", caps={}{}",
+                    names.join("|"),
+                    if *effective { "+ep" } else { "+p" }
+                );
+            }
+
+            if issues.is_empty() {
+                continue;
+            }
+
+            let mut kinds = Vec::new();
+            if setuid {
+                kinds.push("suid");
+            }
+            if setgid {
+                kinds.push("sgid");
+            }
+            if caps.is_some() {
+                kinds.push("file_caps");
+            }
+
+            findings.push(format!(
+                "path={}, kind={}, mode={:o}, uid={}, gid={}{}, issues={}",
+                path.display(),
+                kinds.join("|"),
+                mode & 0o7777,
+                meta.uid(),
+                meta.gid(),
+                cap_summary,
+                issues.join(",")
+            ));
+        }
+    }
+}
+
+fn directory_writable(dir: &Path) -> bool {
+    match fs::metadata(dir) {
+        Ok(meta) => meta.uid() != 0 || (meta.mode() & 0o022 != 0 && meta.mode() & 0o1000 == 0),
+        Err(_) => false,
+    }
+}
+
+fn read_file_caps(path: &Path) -> Option<(Vec<&'static str>, bool)> {
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let name = b"security.capability\0";
+    let mut buf = [0u8; 24];
+    let len = unsafe {
+        libc::lgetxattr(
+            c_path.as_ptr(),
+            name.as_ptr() as *const libc::c_char,
+            buf.as_mut_ptr() as *mut libc::c_void,
+            buf.len(),
+        )
+    };
+    if len < 12 {
+        return None;
+    }
+    let len = len as usize;
+
+    let word = |idx: usize| -> u32 {
+        let start = idx * 4;
+        u32::from_le_bytes([buf[start], buf[start + 1], buf[start + 2], buf[start + 3]])
+    };
+
+    // struct vfs_cap_data: magic_etc, then {permitted, inheritable} pairs.
+    let magic = word(0);
+    let effective = magic & 0x1 != 0;
+    let mut permitted = word(1) as u64;
+    if len >= 20 {
+        permitted |= (word(3) as u64) << 32;
+    }
+
+    let names = CAP_NAMES
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| permitted & (1u64 << bit) != 0)
+        .map(|(_, name)| *name)
+        .collect();
+    Some((names, effective))
+}
+
+fn load_dpkg_owned_paths() -> Option<HashSet<PathBuf>> {
+    let entries = fs::read_dir("/var/lib/dpkg/info").ok()?;
+    let mut owned = HashSet::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("list") {
+            continue;
+        }
+        if let Ok(content) = fs::read_to_string(&path) {
+            for line in content.lines() {
+                let line = line.trim();
+                let Some(relative) = line.strip_prefix('/') else {
+                    continue;
+                };
+                owned.insert(PathBuf::from(line));
+                // Packages list /bin paths that merged-usr systems store under /usr.
+                if !relative.starts_with("usr/") {
+                    owned.insert(Path::new("/usr").join(relative));
+                }
+            }
+        }
+    }
+    if owned.is_empty() { None } else { Some(owned) }
+}
@@ -0,0 +1,234 @@
+use crate::ScanOutcome;
+use std::{fs, os::unix::fs::MetadataExt};
+
+const PF_KTHREAD: u64 = 0x0020_0000;
+const VALUE_LIMIT: usize = 128;
+const TMP_PREFIXES: &[&str] = &["/tmp/", "/var/tmp/", "/dev/shm/", "/run/shm/"];
+const SHELLS: &[&str] = &[
+    "sh", "bash", "dash", "zsh", "ksh", "mksh", "fish", "tcsh", "csh",
+];
+
+// Variables that make interpreters load attacker-controlled code at startup.
+const INJECTION_VARS: &[&str] = &[
+    "PYTHONPATH",
+    "PYTHONSTARTUP",
+    "PYTHONHOME",
+    "PERL5LIB",
+    "PERL5OPT",
+    "PERLLIB",
+    "RUBYLIB",
+    "RUBYOPT",
+    "NODE_OPTIONS",
+    "NODE_PATH",
+    "BASH_ENV",
+    "ENV",
+    "JAVA_TOOL_OPTIONS",
+    "_JAVA_OPTIONS",
+    "JDK_JAVA_OPTIONS",
+];
+
+const PROXY_VARS: &[&str] = &[
+    "http_proxy",
+    "https_proxy",
+    "ftp_proxy",
+    "all_proxy",
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "FTP_PROXY",
+    "ALL_PROXY",
+];
+
+pub fn run() -> ScanOutcome {
+    let mut findings = Vec::new();
+    let mut errors = Vec::new();
+
+    let entries = fs::read_dir("/proc").map_err(|err| format!("failed to read /proc: {err}"))?;
+    for entry in entries.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<i32>().ok())
+        else {
+            continue;
+        };
+        match inspect_process(pid) {
+            Ok(Some(record)) => findings.push(record),
+            Ok(None) => {}
+            Err(err) => errors.push(format!("pid={pid}: {err}")),
+        }
+    }
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+fn inspect_process(pid: i32) -> Result<Option<String>, String> {
+    let base = format!("/proc/{pid}");
+    let stat = match fs::read_to_string(format!("{base}/stat")) {
+        Ok(stat) => stat,
+        Err(err) if matches!(err.raw_os_error(), Some(2) | Some(3)) => return Ok(None),
+        Err(err) => return Err(format!("failed to read stat: {err}")),
+    };
+    let Some((_, tail)) = stat.rsplit_once(')') else {
+        return Ok(None);
+    };
+    let fields: Vec<&str> = tail.split_whitespace().collect();
+    let tty_nr: i64 = fields.get(4).and_then(|v| v.parse().ok()).unwrap_or(0);
+    let flags: u64 = fields.get(6).and_then(|v| v.parse().ok()).unwrap_or(0);
+    if flags & PF_KTHREAD != 0 || fields.first() == Some(&"Z") {
+        return Ok(None);
+    }
+    // Without an exe link we cannot tell a wiped process from a vanished one.
+    if fs::read_link(format!("{base}/exe")).is_err() {
+        return Ok(None);
+    }
+
+    let environ = match fs::read(format!("{base}/environ")) {
+        Ok(environ) => environ,
+        Err(err) if matches!(err.raw_os_error(), Some(2) | Some(3) | Some(13)) => {
+            return Ok(None);
+        }
+        Err(err) => return Err(format!("failed to read environ: {err}")),
+    };
+    let cmdline = fs::read(format!("{base}/cmdline")).unwrap_or_default();
+
+    let comm = fs::read_to_string(format!("{base}/comm"))
+        .unwrap_or_else(|_| "unknown".to_string())
+        .trim()
+        .to_string();
+    let daemon = tty_nr == 0;
+    let interactive_shell = !daemon && SHELLS.contains(&comm.trim_start_matches('-'));
+    let root = format!("{base}/root");
+
+    let mut issues = Vec::new();
+
+    if cmdline.is_empty() {
+        issues.push("argv_empty".to_string());
+    } else if cmdline.iter().all(|b| *b == 0) {
+        issues.push("argv_zeroed".to_string());
+    }
+
+    // setproctitle() (sshd, nginx, postgres) reuses the environ area for a
+    // longer title and pads it with NULs, so zeroed environ is expected there.
+    let retitled = {
+        let args: Vec<&[u8]> = cmdline
+            .split(|b| *b == 0)
+            .filter(|a| !a.is_empty())
+            .collect();
+        args.len() == 1 && args[0].contains(&b' ')
+            || args
+                .first()
+                .is_some_and(|arg0| arg0.windows(2).any(|w| w == b": "))
+    };
+    if environ.is_empty() {
+        issues.push("environ_empty".to_string());
+    } else if !retitled && environ.iter().all(|b| *b == 0) {
+        issues.push("environ_zeroed".to_string());
+    }
+
+    for entry in environ.split(|b| *b == 0) {
+        let entry = String::from_utf8_lossy(entry);
+        let Some((key, value)) = entry.split_once('=') else {
+            continue;
+        };
+
+        if key == "LD_LIBRARY_PATH" {
+            // The loader ignores an empty value; only empty elements of a list mean cwd.
+            if value.is_empty() {
+                continue;
+            }
+            for dir in value.split(':') {
+                if let Some(reason) = untrusted_dir(&root, dir) {
+                    issues.push(format!("ld_library_path_{reason}({})", clip(dir)));
+                }
+            }
+            continue;
+        }
+
+        if interactive_shell {
+            let disabled = match key {
+                "HISTFILE" => value.is_empty() || value == "/dev/null",
+                "HISTSIZE" | "HISTFILESIZE" | "SAVEHIST" => value.trim() == "0",
+                _ => false,
+            };
+            if disabled {
+                issues.push(format!("history_disabled({key}={})", clip(value)));
+                continue;
+            }
+        }
+
+        if INJECTION_VARS.contains(&key) {
+            let tainted = value
+                .split([':', ' ', '=', ','])
+                .filter(|part| part.starts_with('/'))
+                .any(|part| untrusted_dir(&root, part).is_some());
+            if daemon || tainted {
+                issues.push(format!("injection_var({key}={})", clip(value)));
+            }
+            continue;
+        }
+
+        if daemon && PROXY_VARS.contains(&key) && !value.is_empty() {
+            issues.push(format!("proxy_in_daemon({key}={})", clip(value)));
+        }
+    }
+
+    if issues.is_empty() {
+        return Ok(None);
+    }
+
+    let uid = fs::metadata(&base)
+        .map(|meta| meta.uid())
+        .unwrap_or(u32::MAX);
+    Ok(Some(format!(
+        "pid={}, comm={}, uid={}, tty={}, issues={}",
+        pid,
+        comm,
+        uid,
+        if daemon { "none" } else { "attached" },
+        issues.join(",")
+    )))
+}
+
+/// Classifies a search path element that lets other users plant code.
+fn untrusted_dir(root: &str, dir: &str) -> Option<&'static str> {
+    if dir.is_empty() || dir == "." {
+        return Some("cwd");
+    }
+    if !dir.starts_with('/') {
+        return Some("relative");
+    }
+    let normalized = format!("{}/", dir.trim_end_matches('/'));
+    if TMP_PREFIXES
+        .iter()
+        .any(|prefix| normalized.starts_with(prefix))
+    {
+        return Some("tmp");
+    }
+    match fs::metadata(format!("{root}{dir}")) {
+        Ok(meta) if meta.is_dir() && meta.mode() & 0o002 != 0 => Some("writable"),
+        _ => None,
+    }
+}
+
+fn clip(value: &str) -> String {
+    if value.len() <= VALUE_LIMIT {
+        return value.to_string();
+    }
+    let mut end = VALUE_LIMIT;
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...", &value[..end])
+}
@@ -0,0 +1,381 @@
+use crate::ScanOutcome;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
+
+const PF_KTHREAD: u64 = 0x0020_0000;
+const COMM_LEN: usize = 15;
+
+const SYSTEM_DIRS: &[&str] = &[
+    "/usr/sbin",
+    "/usr/bin",
+    "/sbin",
+    "/bin",
+    "/usr/lib/systemd",
+    "/lib/systemd",
+    "/usr/libexec",
+    "/usr/lib/openssh",
+];
+
+const SEARCH_PATH: &[&str] = &[
+    "/usr/local/sbin",
+    "/usr/local/bin",
+    "/usr/sbin",
+    "/usr/bin",
+    "/sbin",
+    "/bin",
+];
+
+// Names commonly borrowed to blend in with system services.
+const SYSTEM_DAEMONS: &[&str] = &[
+    "sshd",
+    "systemd",
+    "systemd-journald",
+    "systemd-logind",
+    "systemd-udevd",
+    "systemd-resolved",
+    "systemd-networkd",
+    "systemd-timesyncd",
+    "init",
+    "cron",
+    "crond",
+    "atd",
+    "rsyslogd",
+    "syslogd",
+    "auditd",
+    "dbus-daemon",
+    "polkitd",
+    "agetty",
+    "login",
+    "udevd",
+    "containerd",
+    "dockerd",
+    "kubelet",
+    "chronyd",
+    "ntpd",
+];
+
+// Daemons expected to run once per PID namespace (forked workers share the
+// parent's name and are excluded).
+const SINGLETONS: &[&str] = &[
+    "systemd-journal",
+    "systemd-logind",
+    "systemd-udevd",
+    "systemd-resolve",
+    "systemd-timesyn",
+    "systemd-network",
+    "cron",
+    "crond",
+    "atd",
+    "rsyslogd",
+    "auditd",
+    "polkitd",
+    "chronyd",
+    "containerd",
+    "dockerd",
+    "kubelet",
+];
+
+const INTERPRETERS: &[&str] = &[
+    "sh", "bash", "dash", "zsh", "ksh", "mksh", "fish", "python", "perl", "ruby", "node", "php",
+    "lua", "java", "tclsh", "busybox",
+];
+
+struct ProcessInfo {
+    pid: i32,
+    ppid: i32,
+    comm: String,
+    name: String,
+    argv: Vec<String>,
+    exe: String,
+    pidns: String,
+}
+
+pub fn run() -> ScanOutcome {
+    let mut findings = Vec::new();
+    let mut errors = Vec::new();
+    let mut processes = BTreeMap::new();
+
+    let entries = fs::read_dir("/proc").map_err(|err| format!("failed to read /proc: {err}"))?;
+    for entry in entries.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<i32>().ok())
+        else {
+            continue;
+        };
+        match read_process(pid) {
+            Ok(Some(info)) => {
+                processes.insert(pid, info);
+            }
+            Ok(None) => {}
+            Err(err) => errors.push(format!("pid={pid}: {err}")),
+        }
+    }
+
+    for info in processes.values() {
+        let issues = evaluate(info);
+        if issues.is_empty() {
+            continue;
+        }
+        findings.push(format!(
+            "pid={}, comm={}, name={}, argv0={}, exe={}, issues={}",
+            info.pid,
+            info.comm,
+            info.name,
+            info.argv.first().map(String::as_str).unwrap_or(""),
+            info.exe,
+            issues.join(",")
+        ));
+    }
+
+    let mut singletons: BTreeMap<(&str, &str), BTreeSet<i32>> = BTreeMap::new();
+    for info in processes.values() {
+        if !SINGLETONS.contains(&info.comm.as_str()) {
+            continue;
+        }
+        let forked_worker = processes
+            .get(&info.ppid)
+            .is_some_and(|parent| parent.comm == info.comm);
+        if !forked_worker {
+            singletons
+                .entry((info.comm.as_str(), info.pidns.as_str()))
+                .or_default()
+                .insert(info.pid);
+        }
+    }
+    for ((comm, pidns), pids) in singletons {
+        if pids.len() < 2 {
+            continue;
+        }
+        let exes: BTreeSet<&str> = pids
+            .iter()
+            .filter_map(|pid| processes.get(pid))
+            .map(|info| info.exe.as_str())
+            .collect();
+        findings.push(format!(
+            "comm={}, pidns={}, pids={}, exes={}, issue=duplicate_singleton",
+            comm,
+            pidns,
+            pids.iter()
+                .map(|pid| pid.to_string())
+                .collect::<Vec<_>>()
+                .join("|"),
+            exes.into_iter().collect::<Vec<_>>().join("|")
+        ));
+    }
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+fn read_process(pid: i32) -> Result<Option<ProcessInfo>, String> {
+    let base = format!("/proc/{pid}");
+    let stat = match fs::read_to_string(format!("{base}/stat")) {
+        Ok(stat) => stat,
+        Err(err) if matches!(err.raw_os_error(), Some(2) | Some(3)) => return Ok(None),
+        Err(err) => return Err(format!("failed to read stat: {err}")),
+    };
+    let Some((_, tail)) = stat.rsplit_once(')') else {
+        return Ok(None);
+    };
+    let fields: Vec<&str> = tail.split_whitespace().collect();
+    let ppid = fields.get(1).and_then(|v| v.parse().ok()).unwrap_or(0);
+    let flags: u64 = fields.get(6).and_then(|v| v.parse().ok()).unwrap_or(0);
+    if flags & PF_KTHREAD != 0 {
+        return Ok(None);
+    }
+
+    // Zombies and processes we cannot inspect carry no exe link.
+    let Ok(exe) = fs::read_link(format!("{base}/exe")) else {
+        return Ok(None);
+    };
+    let exe = exe.to_string_lossy().into_owned();
+
+    let cmdline = fs::read(format!("{base}/cmdline")).unwrap_or_default();
+    let argv: Vec<String> = cmdline
+        .split(|b| *b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect();
+
+    let comm = fs::read_to_string(format!("{base}/comm"))
+        .map(|s| s.trim_end_matches('\n').to_string())
+        .unwrap_or_default();
+    let name = fs::read_to_string(format!("{base}/status"))
+        .ok()
+        .and_then(|status| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix("Name:"))
+                .map(|value| value.trim().to_string())
+        })
+        .unwrap_or_default();
+    let pidns = fs::read_link(format!("{base}/ns/pid"))
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    Ok(Some(ProcessInfo {
+        pid,
+        ppid,
+        comm,
+        name,
+        argv,
+        exe,
+        pidns,
+    }))
+}
+
+fn evaluate(info: &ProcessInfo) -> Vec<String> {
+    let mut issues = Vec::new();
+    let exe_path = info.exe.trim_end_matches(" (deleted)");
+    let exe_base = basename(exe_path);
+    let interpreter = is_interpreter(exe_base);
+    let root = format!("/proc/{}/root", info.pid);
+
+    if !info.name.is_empty() && unescape_status_name(&info.name) != info.comm {
+        issues.push("status_name_mismatch".to_string());
+    }
+
+    let kthread_like = |value: &str| value.starts_with('[') && value.ends_with(']');
+    let argv0 = info.argv.first().map(String::as_str).unwrap_or("");
+    if kthread_like(argv0) || kthread_like(&info.argv.join(" ")) || kthread_like(&info.comm) {
+        issues.push("kthread_name_in_userspace".to_string());
+    }
+
+    // systemd names helper processes "(sd-pam)", "(sd-executor)" and similar.
+    let systemd_helper = exe_base == "systemd" && info.comm.starts_with('(');
+    let script_name = |name: &str| {
+        info.argv
+            .iter()
+            .skip(1)
+            .any(|arg| names_related(name, basename(arg)))
+    };
+
+    if !interpreter
+        && !systemd_helper
+        && !names_related(&info.comm, exe_base)
+        && !resolves_to(&root, &info.comm, exe_path)
+    {
+        issues.push(format!("comm_exe_mismatch(exe_base={exe_base})"));
+    } else if interpreter
+        && !names_related(&info.comm, exe_base)
+        && !script_name(&info.comm)
+        && !resolves_to(&root, &info.comm, exe_path)
+    {
+        issues.push(format!("comm_interpreter_mismatch(exe_base={exe_base})"));
+    }
+
+    if let Some(argv0) = info.argv.first() {
+        // Login shells prefix "-", and daemons rewrite argv as "name: detail".
+        let word = argv0.split([' ', ':']).next().unwrap_or("");
+        let word = word.trim_start_matches('-');
+        let argv0_base = basename(word);
+        let consistent = argv0_base.is_empty()
+            || word == "/proc/self/exe"
+            || exe_base == "busybox"
+            || names_related(argv0_base, exe_base)
+            || names_related(&info.comm, argv0_base)
+            || resolves_to(&root, word, exe_path)
+            || (interpreter && script_name(argv0_base));
+        if !consistent {
+            issues.push(format!("argv0_mismatch(argv0_base={argv0_base})"));
+        }
+    }
+
+    let exe_dir = Path::new(exe_path)
+        .parent()
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let in_system_dir = SYSTEM_DIRS.iter().any(|dir| exe_dir == *dir);
+    if SYSTEM_DAEMONS.contains(&exe_base) && !in_system_dir {
+        issues.push("daemon_name_outside_system_dirs".to_string());
+    }
+    let impersonated = SYSTEM_DAEMONS
+        .iter()
+        .filter(|daemon| names_related(&info.comm, daemon) && !names_related(exe_base, daemon))
+        .max_by_key(|daemon| daemon.len());
+    match impersonated {
+        Some(daemon) if !interpreter || !script_name(&info.comm) => {
+            issues.push(format!("impersonates={daemon}"));
+        }
+        _ => {}
+    }
+
+    if info.exe.ends_with(" (deleted)") && !issues.is_empty() {
+        issues.push("exe_deleted".to_string());
+    }
+
+    issues
+}
+
+/// Whether two process names refer to the same program, tolerating the
+/// 15-byte comm truncation and versioned binaries such as python3.11.
+fn names_related(a: &str, b: &str) -> bool {
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+    if a == b {
+        return true;
+    }
+    let (short, long) = if a.len() < b.len() { (a, b) } else { (b, a) };
+    if short.len() == COMM_LEN && long.starts_with(short) {
+        return true;
+    }
+    long.strip_prefix(short)
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(|c| matches!(c, '.' | '-' | '_') || c.is_ascii_digit())
+}
+
+/// Resolves a command name through the process root and PATH to check whether
+/// it is simply an alias (symlink or alternatives entry) of the executable.
+fn resolves_to(root: &str, command: &str, exe: &str) -> bool {
+    if command.is_empty() {
+        return false;
+    }
+    let candidates: Vec<String> = if command.starts_with('/') {
+        vec![command.to_string()]
+    } else if command.contains('/') {
+        return false;
+    } else {
+        SEARCH_PATH
+            .iter()
+            .map(|dir| format!("{dir}/{command}"))
+            .collect()
+    };
+    let exe_target = fs::canonicalize(format!("{root}{exe}")).ok();
+    candidates.iter().any(|candidate| {
+        fs::canonicalize(format!("{root}{candidate}"))
+            .ok()
+            .is_some_and(|target| Some(&target) == exe_target.as_ref())
+    })
+}
+
+fn is_interpreter(exe_base: &str) -> bool {
+    INTERPRETERS
+        .iter()
+        .any(|name| names_related(exe_base, name))
+}
+
+fn basename(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn unescape_status_name(name: &str) -> String {
+    // status escapes "\n" and "\\" in task names.
+    name.replace("\\n", "\n").replace("\\\\", "\\")
+}
@@ -0,0 +1,320 @@
+use super::{
+    hidden_listeners::parse_proc_endpoint,
+    netns_utils::collect_net_namespaces,
+    suspicious_ptrace::{TaskInfo, collect_tasks},
+};
+use crate::ScanOutcome;
+use std::{collections::HashMap, fs};
+
+const MAX_ANCESTRY: usize = 64;
+const TMP_DIRS: &[&str] = &["/tmp", "/var/tmp", "/dev/shm", "/run/shm"];
+
+const SHELLS: &[&str] = &[
+    "sh",
+    "bash",
+    "dash",
+    "zsh",
+    "ksh",
+    "mksh",
+    "ash",
+    "fish",
+    "csh",
+    "tcsh",
+    "busybox",
+    "nc",
+    "ncat",
+    "netcat",
+    "nc.openbsd",
+    "nc.traditional",
+    "socat",
+    "telnet",
+];
+const INTERPRETER_PREFIXES: &[&str] = &["python", "perl", "ruby", "php", "lua", "node"];
+
+// Services that should never need to hand out an interactive shell.
+const SERVICE_PARENTS: &[&str] = &[
+    "nginx",
+    "apache2",
+    "httpd",
+    "lighttpd",
+    "caddy",
+    "php-fpm",
+    "uwsgi",
+    "gunicorn",
+    "tomcat",
+    "catalina",
+    "mysqld",
+    "mariadbd",
+    "postgres",
+    "redis-server",
+    "mongod",
+    "memcached",
+    "elasticsearch",
+    "dockerd",
+    "containerd",
+    "containerd-shim",
+    "containerd-shim-runc-v2",
+    "runc",
+    "crun",
+    "conmon",
+    "crio",
+];
+
+struct SocketEntry {
+    proto: &'static str,
+    local: String,
+    remote: String,
+    state: String,
+}
+
+pub fn run() -> ScanOutcome {
+    let tasks = collect_tasks()?;
+    let (sockets, errors) = collect_sockets();
+
+    let mut findings = Vec::new();
+
+    for task in tasks.values() {
+        if !is_shell(&task.comm) {
+            continue;
+        }
+
+        let base = format!("/proc/{}", task.pid);
+        let mut issues = Vec::new();
+        let mut peers = Vec::new();
+
+        let mut socket_stdio = Vec::new();
+        for fd in 0..3 {
+            // Unix sockets (journald, pipes from supervisors) are routine;
+            // only inet sockets count.
+            let Some(entry) =
+                socket_inode(&format!("{base}/fd/{fd}")).and_then(|inode| sockets.get(&inode))
+            else {
+                continue;
+            };
+            socket_stdio.push(fd.to_string());
+            let peer = describe(entry);
+            if !peers.contains(&peer) {
+                peers.push(peer);
+            }
+        }
+        if !socket_stdio.is_empty() {
+            issues.push(format!("socket_stdio(fd={})", socket_stdio.join("|")));
+        }
+
+        let ancestry = ancestry(&tasks, task);
+        let service = ancestry
+            .iter()
+            .skip(1)
+            .find(|ancestor| is_service(&ancestor.comm));
+        if let Some(service) = service {
+            issues.push(format!(
+                "spawned_by_service({}:{})",
+                service.pid, service.comm
+            ));
+        }
+
+        if let Ok(cwd) = fs::read_link(format!("{base}/cwd")) {
+            let cwd = cwd.to_string_lossy();
+            let in_tmp = TMP_DIRS
+                .iter()
+                .any(|dir| cwd == *dir || cwd.starts_with(&format!("{dir}/")));
+            if in_tmp {
+                issues.push(format!("cwd_tmp({cwd})"));
+            }
+        }
+
+        if adopted_by_reaper(&tasks, task) {
+            let network: Vec<String> = fd_socket_inodes(&base)
+                .iter()
+                .filter_map(|inode| sockets.get(inode))
+                .filter(|entry| entry.state != "0A")
+                .map(describe)
+                .collect();
+            if !network.is_empty() {
+                issues.push("orphan_with_network".to_string());
+                for peer in network {
+                    if !peers.contains(&peer) {
+                        peers.push(peer);
+                    }
+                }
+            }
+        }
+
+        if issues.is_empty() {
+            continue;
+        }
+
+        let chain: Vec<String> = ancestry
+            .iter()
+            .rev()
+            .map(|ancestor| format!("{}:{}", ancestor.pid, ancestor.comm))
+            .collect();
+        let mut record = format!(
+            "pid={}, comm={}, uid={}, chain={}, issues={}",
+            task.pid,
+            task.comm,
+            task.uid,
+            chain.join(">"),
+            issues.join(",")
+        );
+        if !peers.is_empty() {
+            record.push_str(&format!(", sockets={}", peers.join("|")));
+        }
+        findings.push(record);
+    }
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+fn is_shell(comm: &str) -> bool {
+    if SHELLS.contains(&comm) {
+        return true;
+    }
+    INTERPRETER_PREFIXES.iter().any(|prefix| {
+        comm.strip_prefix(prefix).is_some_and(|rest| {
+            rest.is_empty() || rest.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        })
+    })
+}
+
+fn is_service(comm: &str) -> bool {
+    SERVICE_PARENTS.iter().any(|name| {
+        comm == *name
+            || comm
+                .strip_prefix(name)
+                .is_some_and(|rest| rest.starts_with([':', '-', ' ']))
+    }) || comm.starts_with("containerd-shim")
+}
+
+/// Orphans are reparented to the nearest child subreaper rather than always
+/// to pid 1: a `systemd` user manager or the init of a pid namespace.
+fn adopted_by_reaper(tasks: &HashMap<i32, TaskInfo>, task: &TaskInfo) -> bool {
+    if task.pid == 1 {
+        return false;
+    }
+    if task.ppid == 1 {
+        return true;
+    }
+    match tasks.get(&task.ppid) {
+        Some(parent) if parent.comm == "systemd" => true,
+        Some(parent) => namespace_init(parent.pid),
+        None => false,
+    }
+}
+
+fn namespace_init(pid: i32) -> bool {
+    fs::read_to_string(format!("/proc/{pid}/status"))
+        .ok()
+        .and_then(|status| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix("NSpid:"))
+                .and_then(|ids| ids.split_whitespace().last())
+                .map(|id| id == "1")
+        })
+        .unwrap_or(false)
+}
+
+/// The task itself followed by its parents up to init.
+fn ancestry<'a>(tasks: &'a HashMap<i32, TaskInfo>, task: &'a TaskInfo) -> Vec<&'a TaskInfo> {
+    let mut chain = vec![task];
+    let mut current = task;
+    while chain.len() < MAX_ANCESTRY && current.ppid > 0 {
+        let Some(parent) = tasks.get(&current.ppid) else {
+            break;
+        };
+        if chain.iter().any(|seen| seen.pid == parent.pid) {
+            break;
+        }
+        chain.push(parent);
+        current = parent;
+    }
+    chain
+}
+
+fn socket_inode(link: &str) -> Option<String> {
+    let target = fs::read_link(link).ok()?;
+    let target = target.to_string_lossy();
+    target
+        .strip_prefix("socket:[")
+        .and_then(|rest| rest.strip_suffix(']'))
+        .map(str::to_string)
+}
+
+fn fd_socket_inodes(base: &str) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(format!("{base}/fd")) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| socket_inode(&entry.path().to_string_lossy()))
+        .collect()
+}
+
+fn describe(entry: &SocketEntry) -> String {
+    format!("{}:{}->{}", entry.proto, entry.local, entry.remote)
+}
+
+// Socket inodes are unique system-wide, so every namespace feeds one map.
+fn collect_sockets() -> (HashMap<String, SocketEntry>, Vec<String>) {
+    let (namespaces, mut errors) = match collect_net_namespaces() {
+        Ok(found) => found,
+        Err(err) => return (HashMap::new(), vec![err]),
+    };
+    let mut map = HashMap::new();
+    for namespace in &namespaces {
+        match namespace.enter(read_sockets) {
+            Ok(found) => map.extend(found),
+            Err(err) => errors.push(format!("netns={}: {err}", namespace.inum)),
+        }
+    }
+    (map, errors)
+}
+
+fn read_sockets(proc_net: &str) -> HashMap<String, SocketEntry> {
+    let mut map = HashMap::new();
+    for (name, proto, ipv6) in [
+        ("tcp", "tcp", false),
+        ("tcp6", "tcp6", true),
+        ("udp", "udp", false),
+        ("udp6", "udp6", true),
+    ] {
+        let Ok(content) = fs::read_to_string(format!("{proc_net}/{name}")) else {
+            continue;
+        };
+        for line in content.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 || fields[9] == "0" {
+                continue;
+            }
+            let (Some(local), Some(remote)) = (
+                parse_proc_endpoint(fields[1], ipv6),
+                parse_proc_endpoint(fields[2], ipv6),
+            ) else {
+                continue;
+            };
+            map.insert(
+                fields[9].to_string(),
+                SocketEntry {
+                    proto,
+                    local,
+                    remote,
+                    state: fields[3].to_string(),
+                },
+            );
+        }
+    }
+    map
+}
@@ -0,0 +1,310 @@
+use super::ssh_footholds::{PasswdEntry, read_passwd};
+use crate::ScanOutcome;
+use std::{fs, os::unix::fs::MetadataExt, path::Path};
+
+const SYSTEM_FILES: &[&str] = &[
+    "/etc/profile",
+    "/etc/bash.bashrc",
+    "/etc/bashrc",
+    "/etc/zsh/zshrc",
+    "/etc/zsh/zshenv",
+    "/etc/zshrc",
+    "/etc/zshenv",
+    "/etc/environment",
+];
+
+const SYSTEM_DIRS: &[&str] = &["/etc/profile.d"];
+
+const USER_FILES: &[&str] = &[
+    ".bashrc",
+    ".bash_profile",
+    ".bash_login",
+    ".bash_logout",
+    ".profile",
+    ".zshrc",
+    ".zshenv",
+    ".zprofile",
+    ".zlogin",
+    ".pam_environment",
+];
+
+const CORE_TOOLS: &[&str] = &[
+    "ls",
+    "ps",
+    "netstat",
+    "ss",
+    "top",
+    "lsof",
+    "find",
+    "kill",
+    "sudo",
+    "su",
+    "ssh",
+    "cat",
+    "grep",
+    "last",
+    "who",
+    "w",
+    "id",
+    "passwd",
+    "crontab",
+    "systemctl",
+];
+
+const FETCHERS: &[&str] = &["curl", "wget", "fetch"];
+const SHELLS: &[&str] = &["sh", "bash", "zsh", "dash", "python", "python3", "perl"];
+const WRAPPERS: &[&str] = &["sudo", "doas", "env", "nohup", "exec", "command"];
+
+pub fn run() -> ScanOutcome {
+    let mut findings = Vec::new();
+    let mut errors = Vec::new();
+
+    for file in SYSTEM_FILES {
+        let path = Path::new(file);
+        if !path.exists() {
+            continue;
+        }
+        match analyze_file(None, path) {
+            Ok(mut list) => findings.append(&mut list),
+            Err(err) => errors.push(err),
+        }
+    }
+
+    for dir in SYSTEM_DIRS {
+        let dir = Path::new(dir);
+        if !dir.exists() {
+            continue;
+        }
+        match fs::read_dir(dir) {
+            Ok(entries) => {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if !path.is_file() {
+                        continue;
+                    }
+                    match analyze_file(None, &path) {
+                        Ok(mut list) => findings.append(&mut list),
+                        Err(err) => errors.push(err),
+                    }
+                }
+            }
+            Err(err) => errors.push(format!("failed to read {}: {err}", dir.display())),
+        }
+    }
+
+    match read_passwd() {
+        Ok(users) => {
+            for entry in users {
+                if !entry.home.is_dir() || entry.home == Path::new("/") {
+                    continue;
+                }
+                for name in USER_FILES {
+                    let path = entry.home.join(name);
+                    if !path.is_file() {
+                        continue;
+                    }
+                    match analyze_file(Some(&entry), &path) {
+                        Ok(mut list) => findings.append(&mut list),
+                        Err(err) => errors.push(err),
+                    }
+                }
+            }
+        }
+        Err(err) => errors.push(err),
+    }
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        findings.dedup();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+fn analyze_file(owner: Option<&PasswdEntry>, path: &Path) -> Result<Vec<String>, String> {
+    let metadata =
+        fs::metadata(path).map_err(|err| format!("failed to stat {}: {err}", path.display()))?;
+    let content =
+        fs::read(path).map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+    let content = String::from_utf8_lossy(&content);
+
+    let prefix = match owner {
+        Some(owner) => format!("user={}, file={}", owner.user, path.display()),
+        None => format!("file={}", path.display()),
+    };
+    let owner_uid = owner.map_or(0, |owner| owner.uid);
+    let home = owner.map(|owner| owner.home.as_path());
+
+    let mut findings = Vec::new();
+
+    if metadata.mode() & 0o022 != 0 {
+        findings.push(format!("{prefix}, indicator=perms_insecure"));
+    }
+    if metadata.uid() != 0 && metadata.uid() != owner_uid {
+        findings.push(format!(
+            "{prefix}, indicator=foreign_owner, owner_uid={}",
+            metadata.uid()
+        ));
+    }
+
+    for (idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        for indicator in evaluate_line(trimmed, home) {
+            findings.push(format!(
+                "{prefix}, line_no={}, indicator={}, entry='{}'",
+                idx + 1,
+                indicator,
+                trimmed
+            ));
+        }
+    }
+
+    Ok(findings)
+}
+
+fn evaluate_line(line: &str, home: Option<&Path>) -> Vec<&'static str> {
+    let mut indicators = Vec::new();
+
+    if sets_variable(line, "LD_PRELOAD") {
+        indicators.push("ld_preload");
+    }
+    if sets_variable(line, "LD_AUDIT") {
+        indicators.push("ld_audit");
+    }
+    if sets_variable(line, "PROMPT_COMMAND") {
+        indicators.push("prompt_command");
+    }
+    if aliases_core_tool(line) {
+        indicators.push("core_tool_alias");
+    }
+    if fetches_and_executes(line) {
+        indicators.push("remote_fetch_exec");
+    }
+    if line.contains("/dev/tcp/") || line.contains("/dev/udp/") {
+        indicators.push("dev_tcp_redirect");
+    }
+    for token in line
+        .split(|c: char| c.is_whitespace() || matches!(c, '=' | ';' | '"' | '\'' | '`' | '(' | ')'))
+    {
+        if looks_temporary(token) {
+            indicators.push("tmp_path");
+            break;
+        }
+        if hidden_executable(token, home) {
+            indicators.push("hidden_executable");
+            break;
+        }
+    }
+
+    indicators
+}
+
+fn sets_variable(line: &str, name: &str) -> bool {
+    let mut words: Vec<&str> = line
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .filter(|word| !word.is_empty())
+        .collect();
+    if matches!(
+        words.first(),
+        Some(&("export" | "declare" | "typeset" | "readonly" | "local"))
+    ) {
+        words.remove(0);
+        words.retain(|word| !word.starts_with('-'));
+    }
+    // .pam_environment uses `NAME DEFAULT=... OVERRIDE=...`.
+    if words.first() == Some(&name) && words.len() > 1 {
+        return true;
+    }
+    words.iter().any(|word| {
+        word.strip_prefix(name)
+            .is_some_and(|rest| rest.starts_with('=') || rest.starts_with("+="))
+    })
+}
+
+fn aliases_core_tool(line: &str) -> bool {
+    let Some(rest) = line.strip_prefix("alias ") else {
+        return false;
+    };
+    let Some((name, value)) = rest.trim().split_once('=') else {
+        return false;
+    };
+    if !CORE_TOOLS.contains(&name) {
+        return false;
+    }
+    let value = value.trim().trim_matches(['"', '\'']);
+    // Distro defaults such as `alias ls='ls --color=auto'` only add flags.
+    let same_tool = value
+        .split_whitespace()
+        .next()
+        .map(|cmd| basename(cmd) == name)
+        .unwrap_or(false);
+    let chained =
+        value.contains(['|', ';', '`', '>']) || value.contains("$(") || value.contains("&&");
+    let hiding = value.contains("--hide") || value.contains("--ignore");
+    !same_tool || chained || hiding
+}
+
+fn fetches_and_executes(line: &str) -> bool {
+    let words: Vec<&str> = line
+        .split(|c: char| c.is_whitespace() || matches!(c, '|' | '(' | ')' | '$' | '"' | '\'' | ';'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    let fetches = words.iter().any(|w| FETCHERS.contains(&basename(w)));
+    if !fetches {
+        return false;
+    }
+    let piped_to_shell = line.split('|').skip(1).any(|segment| {
+        segment
+            .split_whitespace()
+            .find(|word| !WRAPPERS.contains(word) && !word.starts_with('-'))
+            .is_some_and(|cmd| SHELLS.contains(&basename(cmd)))
+    });
+    let substituted = line
+        .split_whitespace()
+        .find(|word| !WRAPPERS.contains(word))
+        .is_some_and(|cmd| {
+            SHELLS.contains(&basename(cmd)) || matches!(cmd, "eval" | "source" | ".")
+        })
+        && (line.contains("$(") || line.contains("<(") || line.contains('`'));
+    piped_to_shell || substituted
+}
+
+fn basename(token: &str) -> &str {
+    token.rsplit('/').next().unwrap_or(token)
+}
+
+fn looks_temporary(token: &str) -> bool {
+    token.starts_with("/tmp/") || token.starts_with("/var/tmp/") || token.starts_with("/dev/shm/")
+}
+
+fn hidden_executable(token: &str, home: Option<&Path>) -> bool {
+    let relative = ["~/", "$HOME/", "${HOME}/"]
+        .iter()
+        .find_map(|prefix| token.strip_prefix(prefix));
+    let path = match (relative, home) {
+        (Some(relative), Some(home)) => home.join(relative),
+        (None, _) if token.starts_with('/') => Path::new(token).to_path_buf(),
+        _ => return false,
+    };
+    let hidden = path.components().any(|component| {
+        component
+            .as_os_str()
+            .to_str()
+            .is_some_and(|name| name.starts_with('.') && name != "." && name != "..")
+    });
+    hidden
+        && fs::metadata(&path)
+            .map(|meta| meta.is_file() && meta.mode() & 0o111 != 0)
+            .unwrap_or(false)
+}
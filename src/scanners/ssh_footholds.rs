@@ -1,49 +1,524 @@
 use crate::ScanOutcome;
-use std::{fs, os::unix::fs::MetadataExt, path::Path};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs,
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+};
 
-pub fn run() -> ScanOutcome {
-    let passwd = match fs::read_to_string("/etc/passwd") {
-        Ok(content) => content,
-        Err(err) => return Err(format!("failed to read /etc/passwd: {err}")),
-    };
+const SSHD_CONFIG: &str = "/etc/ssh/sshd_config";
+const SSHD_CONFIG_DIR: &str = "/etc/ssh";
+const SYSTEM_SSHRC: &str = "/etc/ssh/sshrc";
+const DEFAULT_AUTHORIZED_KEYS: &[&str] = &[".ssh/authorized_keys", ".ssh/authorized_keys2"];
+const MAX_INCLUDE_DEPTH: usize = 16;
+pub const INVALID_ID: u32 = u32::MAX;
 
-    let mut findings = Vec::new();
+pub struct PasswdEntry {
+    pub user: String,
+    pub password: String,
+    pub uid: u32,
+    pub gid: u32,
+    pub home: PathBuf,
+    pub shell: String,
+}
+
+pub fn read_passwd() -> Result<Vec<PasswdEntry>, String> {
+    let passwd = fs::read_to_string("/etc/passwd")
+        .map_err(|err| format!("failed to read /etc/passwd: {err}"))?;
 
+    let mut entries = Vec::new();
     for line in passwd.lines() {
         let parts: Vec<&str> = line.split(':').collect();
         if parts.len() < 6 {
             continue;
         }
-        let user = parts[0];
-        let home = Path::new(parts[5]);
-        if !home.exists() {
-            continue;
+        entries.push(PasswdEntry {
+            user: parts[0].to_string(),
+            password: parts[1].to_string(),
+            uid: parts[2].parse().unwrap_or(INVALID_ID),
+            gid: parts[3].parse().unwrap_or(INVALID_ID),
+            home: PathBuf::from(parts[5]),
+            shell: parts.get(6).copied().unwrap_or("").to_string(),
+        });
+    }
+
+    Ok(entries)
+}
+
+pub fn is_nologin_shell(shell: &str) -> bool {
+    shell.ends_with("/nologin") || shell.ends_with("/false") || shell == "/bin/sync"
+}
+
+struct SshdDirective {
+    keyword: String,
+    value: String,
+    file: PathBuf,
+    line_no: usize,
+    match_block: Option<String>,
+}
+
+struct KeyRecord {
+    user: String,
+    file: PathBuf,
+    key_type: String,
+    fingerprint: String,
+    comment: String,
+}
+
+pub fn run() -> ScanOutcome {
+    let users = read_passwd()?;
+
+    let mut findings = Vec::new();
+    let mut errors = Vec::new();
+
+    let mut directives = Vec::new();
+    let config = Path::new(SSHD_CONFIG);
+    if config.exists() {
+        let mut visited = HashSet::new();
+        parse_sshd_config(config, None, 0, &mut visited, &mut directives, &mut errors);
+        findings.extend(evaluate_directives(&directives));
+    }
+
+    let key_patterns = authorized_keys_patterns(&directives);
+
+    // Presence of an rc hook or key is only reported next to a real issue.
+    let mut context = Vec::new();
+    if let Some((record, suspicious)) = inspect_rc_file(None, Path::new(SYSTEM_SSHRC)) {
+        if suspicious {
+            findings.push(record);
+        } else {
+            context.push(record);
         }
-        let auth_path = home.join(".ssh").join("authorized_keys");
-        if !auth_path.exists() {
+    }
+
+    let mut keys = Vec::new();
+    let mut flagged_files = HashSet::new();
+
+    for entry in &users {
+        let user = entry.user.as_str();
+        if !entry.home.exists() {
             continue;
         }
 
-        match analyze_file(user, &auth_path) {
-            Ok(mut items) => findings.append(&mut items),
-            Err(err) => findings.push(format!(
-                "user={}, file={}, error={}",
-                user,
-                auth_path.display(),
-                err
-            )),
+        if let Some((record, suspicious)) =
+            inspect_rc_file(Some(user), &entry.home.join(".ssh").join("rc"))
+        {
+            if suspicious {
+                findings.push(record);
+            } else {
+                context.push(record);
+            }
+        }
+
+        for auth_path in expand_authorized_keys(&key_patterns, entry) {
+            if !auth_path.exists() {
+                continue;
+            }
+
+            match analyze_file(entry, &auth_path, &mut keys) {
+                Ok(mut items) => {
+                    if !items.is_empty() {
+                        flagged_files.insert(auth_path.clone());
+                    }
+                    findings.append(&mut items);
+                }
+                Err(err) => findings.push(format!(
+                    "user={}, file={}, error={}",
+                    user,
+                    auth_path.display(),
+                    err
+                )),
+            }
+        }
+    }
+
+    let mut owners_by_key: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for key in &keys {
+        owners_by_key
+            .entry(key.fingerprint.as_str())
+            .or_default()
+            .insert(key.user.as_str());
+    }
+    for key in &keys {
+        let duplicated = owners_by_key
+            .get(key.fingerprint.as_str())
+            .is_some_and(|owners| owners.len() > 1);
+        if !duplicated && !flagged_files.contains(&key.file) {
+            continue;
         }
+        findings.push(format!(
+            "inventory user={}, file={}, key_type={}, fingerprint={}, key_comment={}",
+            key.user,
+            key.file.display(),
+            key.key_type,
+            key.fingerprint,
+            key.comment
+        ));
+    }
+    for (fingerprint, owners) in owners_by_key {
+        if owners.len() > 1 {
+            findings.push(format!(
+                "fingerprint={}, duplicate_across_users={}",
+                fingerprint,
+                owners.into_iter().collect::<Vec<_>>().join("|")
+            ));
+        }
+    }
+
+    if !findings.is_empty() {
+        findings.append(&mut context);
     }
 
     if findings.is_empty() {
-        Ok(None)
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
     } else {
         findings.sort();
+        findings.dedup();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
         Ok(Some(findings.join("\n")))
     }
 }
 
-fn analyze_file(user: &str, path: &Path) -> Result<Vec<String>, String> {
+fn parse_sshd_config(
+    path: &Path,
+    inherited_match: Option<&str>,
+    depth: usize,
+    visited: &mut HashSet<PathBuf>,
+    directives: &mut Vec<SshdDirective>,
+    errors: &mut Vec<String>,
+) {
+    if depth > MAX_INCLUDE_DEPTH {
+        errors.push(format!("include depth exceeded at {}", path.display()));
+        return;
+    }
+    if !visited.insert(path.to_path_buf()) {
+        return;
+    }
+
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) => {
+            errors.push(format!("failed to read {}: {err}", path.display()));
+            return;
+        }
+    };
+
+    // A Match block opened inside an included file ends with that file.
+    let mut match_block = inherited_match.map(str::to_string);
+
+    for (idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let (keyword, value) = match trimmed.find(|c: char| c.is_whitespace() || c == '=') {
+            Some(pos) => (
+                &trimmed[..pos],
+                trimmed[pos..].trim_start_matches(|c: char| c.is_whitespace() || c == '='),
+            ),
+            None => (trimmed, ""),
+        };
+        let keyword = keyword.to_ascii_lowercase();
+        let value = value.trim();
+
+        match keyword.as_str() {
+            "match" => {
+                match_block = if value.eq_ignore_ascii_case("all") {
+                    None
+                } else {
+                    Some(value.to_string())
+                };
+            }
+            "include" => {
+                for pattern in value.split_whitespace() {
+                    for include in expand_include(pattern) {
+                        parse_sshd_config(
+                            &include,
+                            match_block.as_deref(),
+                            depth + 1,
+                            visited,
+                            directives,
+                            errors,
+                        );
+                    }
+                }
+            }
+            _ => directives.push(SshdDirective {
+                keyword,
+                value: value.to_string(),
+                file: path.to_path_buf(),
+                line_no: idx + 1,
+                match_block: match_block.clone(),
+            }),
+        }
+    }
+}
+
+fn expand_include(pattern: &str) -> Vec<PathBuf> {
+    let pattern = if pattern.starts_with('/') {
+        PathBuf::from(pattern)
+    } else {
+        Path::new(SSHD_CONFIG_DIR).join(pattern)
+    };
+
+    let Some(name) = pattern.file_name().and_then(|n| n.to_str()) else {
+        return Vec::new();
+    };
+    if !name.contains(['*', '?']) {
+        return vec![pattern];
+    }
+
+    let Some(dir) = pattern.parent() else {
+        return Vec::new();
+    };
+    let mut matches: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .map(|candidate| wildcard_match(name, candidate))
+                        .unwrap_or(false)
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    matches.sort();
+    matches
+}
+
+fn wildcard_match(pattern: &str, candidate: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let candidate: Vec<char> = candidate.chars().collect();
+    let (mut p, mut c) = (0usize, 0usize);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while c < candidate.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == candidate[c]) {
+            p += 1;
+            c += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, c));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            c = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|ch| *ch == '*')
+}
+
+fn evaluate_directives(directives: &[SshdDirective]) -> Vec<String> {
+    let mut findings = Vec::new();
+    // sshd honours the first value it sees for a global keyword.
+    let mut seen_global = HashSet::new();
+
+    for directive in directives {
+        let effective = match directive.match_block {
+            Some(_) => true,
+            None => seen_global.insert(directive.keyword.clone()),
+        };
+        if !effective {
+            continue;
+        }
+
+        let value = directive.value.as_str();
+        let mut issues = Vec::new();
+
+        match directive.keyword.as_str() {
+            "permitrootlogin" if value.eq_ignore_ascii_case("yes") => {
+                issues.push("root_password_login");
+            }
+            "permituserenvironment" if !value.eq_ignore_ascii_case("no") => {
+                issues.push("user_environment_enabled");
+            }
+            "authorizedkeyscommand" if !value.eq_ignore_ascii_case("none") => {
+                issues.push("keys_from_command");
+                if let Some(program) = value.split_whitespace().next() {
+                    issues.extend(untrusted_program(program));
+                }
+            }
+            "forcecommand" => {
+                issues.push("force_command");
+                if let Some(program) = value.split_whitespace().next() {
+                    issues.extend(untrusted_program(program));
+                }
+            }
+            "authorizedkeysfile" => {
+                let paths: Vec<&str> = value.split_whitespace().collect();
+                if paths.iter().any(|path| {
+                    let relative = path
+                        .strip_prefix("%h/")
+                        .or_else(|| path.strip_prefix("~/"))
+                        .unwrap_or(path);
+                    !DEFAULT_AUTHORIZED_KEYS.contains(&relative)
+                }) {
+                    issues.push("non_default_authorized_keys_file");
+                }
+                if paths.iter().any(|path| looks_temporary(path)) {
+                    issues.push("authorized_keys_in_tmp");
+                }
+            }
+            _ => {}
+        }
+
+        if issues.is_empty() {
+            continue;
+        }
+
+        findings.push(format!(
+            "config={}, line_no={}, match={}, directive={}, value={}, issues={}",
+            directive.file.display(),
+            directive.line_no,
+            directive.match_block.as_deref().unwrap_or("∅"),
+            directive.keyword,
+            value,
+            issues.join("|")
+        ));
+    }
+
+    findings
+}
+
+fn untrusted_program(program: &str) -> Vec<&'static str> {
+    let mut issues = Vec::new();
+    if looks_temporary(program) {
+        issues.push("program_in_tmp");
+    }
+    match fs::metadata(program) {
+        Ok(meta) => {
+            if meta.uid() != 0 {
+                issues.push("program_non_root_owner");
+            }
+            if meta.mode() & 0o022 != 0 {
+                issues.push("program_group_or_world_writable");
+            }
+        }
+        Err(_) if program.starts_with('/') => issues.push("program_missing"),
+        Err(_) => {}
+    }
+    issues
+}
+
+fn authorized_keys_patterns(directives: &[SshdDirective]) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut global_seen = false;
+
+    for directive in directives {
+        if directive.keyword != "authorizedkeysfile" {
+            continue;
+        }
+        if directive.match_block.is_none() {
+            if global_seen {
+                continue;
+            }
+            global_seen = true;
+        }
+        for path in directive.value.split_whitespace() {
+            if !path.eq_ignore_ascii_case("none") && !patterns.iter().any(|p| p == path) {
+                patterns.push(path.to_string());
+            }
+        }
+    }
+
+    if !global_seen {
+        for path in DEFAULT_AUTHORIZED_KEYS {
+            if !patterns.iter().any(|p| p == path) {
+                patterns.push(path.to_string());
+            }
+        }
+    }
+
+    patterns
+}
+
+fn expand_authorized_keys(patterns: &[String], entry: &PasswdEntry) -> Vec<PathBuf> {
+    let home = entry.home.to_string_lossy();
+    let mut paths = Vec::new();
+
+    for pattern in patterns {
+        let mut expanded = String::new();
+        let mut chars = pattern.chars();
+        while let Some(ch) = chars.next() {
+            if ch != '%' {
+                expanded.push(ch);
+                continue;
+            }
+            match chars.next() {
+                Some('h') => expanded.push_str(&home),
+                Some('u') => expanded.push_str(&entry.user),
+                Some('U') => expanded.push_str(&entry.uid.to_string()),
+                Some('%') => expanded.push('%'),
+                Some(other) => {
+                    expanded.push('%');
+                    expanded.push(other);
+                }
+                None => expanded.push('%'),
+            }
+        }
+
+        let path = if let Some(rest) = expanded.strip_prefix("~/") {
+            entry.home.join(rest)
+        } else if expanded.starts_with('/') {
+            PathBuf::from(expanded)
+        } else {
+            entry.home.join(expanded)
+        };
+
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+
+    paths
+}
+
+fn inspect_rc_file(user: Option<&str>, path: &Path) -> Option<(String, bool)> {
+    let metadata = fs::symlink_metadata(path).ok()?;
+    let mut issues = vec!["present"];
+    if metadata.mode() & 0o022 != 0 {
+        issues.push("group_or_world_writable");
+    }
+    if metadata.file_type().is_symlink() {
+        issues.push("symlink");
+    }
+    let prefix = match user {
+        Some(user) => format!("user={}, ", user),
+        None => String::new(),
+    };
+    let suspicious = issues.len() > 1;
+    Some((
+        format!(
+            "{}file={}, ssh_rc={}",
+            prefix,
+            path.display(),
+            issues.join("|")
+        ),
+        suspicious,
+    ))
+}
+
+fn analyze_file(
+    entry: &PasswdEntry,
+    path: &Path,
+    keys: &mut Vec<KeyRecord>,
+) -> Result<Vec<String>, String> {
+    let user = entry.user.as_str();
     let mut findings = Vec::new();
     let metadata =
         fs::metadata(path).map_err(|err| format!("failed to stat {}: {err}", path.display()))?;
@@ -59,30 +534,44 @@ fn analyze_file(user: &str, path: &Path) -> Result<Vec<String>, String> {
     let content = fs::read_to_string(path)
         .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
 
+    let mut key_count = 0usize;
+
     for line in content.lines() {
         let trimmed = line.trim();
         if trimmed.is_empty() || trimmed.starts_with('#') {
             continue;
         }
 
-        let mut tokens = trimmed.split_whitespace();
-        let first = tokens.next().unwrap_or("");
-
-        let (options, key_tokens): (Option<&str>, Vec<&str>) = if first.starts_with("ssh-") {
-            (None, std::iter::once(first).chain(tokens).collect())
-        } else {
-            (Some(first), tokens.collect())
+        let Some((options, key_tokens)) = split_key_line(trimmed) else {
+            continue;
         };
+        let key_type = key_tokens.first().copied().unwrap_or("");
+        let blob = key_tokens.get(1).copied().unwrap_or("");
+        let comment = key_tokens
+            .get(2..)
+            .map(|rest| rest.join(" "))
+            .unwrap_or_default();
+        key_count += 1;
+
+        if let Some(fingerprint) = fingerprint(blob) {
+            keys.push(KeyRecord {
+                user: user.to_string(),
+                file: path.to_path_buf(),
+                key_type: key_type.to_string(),
+                fingerprint,
+                comment: comment.clone(),
+            });
+        }
 
         if let Some(opts) = options {
-            let comment = key_tokens.get(2).copied().unwrap_or("");
-            for opt in opts.split(',') {
+            for opt in split_options(opts) {
                 let opt = opt.trim();
                 if opt.is_empty() {
                     continue;
                 }
                 if opt.starts_with("command=")
                     || opt.starts_with("permitopen=")
+                    || opt.starts_with("environment=")
                     || (opt.starts_with("from=") && opt.contains('*'))
                 {
                     findings.push(format!(
@@ -97,5 +586,132 @@ fn analyze_file(user: &str, path: &Path) -> Result<Vec<String>, String> {
         }
     }
 
+    if key_count > 0 && is_nologin_shell(&entry.shell) {
+        findings.push(format!(
+            "user={}, file={}, shell={}, keys={}, keys_for_nologin_account=true",
+            user,
+            path.display(),
+            entry.shell,
+            key_count
+        ));
+    }
+
     Ok(findings)
 }
+
+fn is_key_type(token: &str) -> bool {
+    token.starts_with("ssh-") || token.starts_with("ecdsa-sha2-") || token.starts_with("sk-")
+}
+
+fn split_key_line(line: &str) -> Option<(Option<&str>, Vec<&str>)> {
+    let first = line.split_whitespace().next()?;
+    if is_key_type(first) {
+        return Some((None, line.split_whitespace().collect()));
+    }
+
+    // Options may contain quoted values with embedded whitespace.
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut end = line.len();
+    for (idx, ch) in line.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match ch {
+            '\\' => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                end = idx;
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let options = &line[..end];
+    let key_tokens: Vec<&str> = line[end..].split_whitespace().collect();
+    if key_tokens.is_empty() {
+        return None;
+    }
+    Some((Some(options), key_tokens))
+}
+
+fn split_options(options: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut start = 0usize;
+    for (idx, ch) in options.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match ch {
+            '\\' => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => {
+                parts.push(&options[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&options[start..]);
+    parts
+}
+
+fn fingerprint(blob: &str) -> Option<String> {
+    let decoded = base64_decode(blob)?;
+    let digest = Sha256::digest(&decoded);
+    Some(format!("SHA256:{}", base64_encode_unpadded(&digest)))
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_decode(input: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0u32;
+
+    for byte in input.bytes() {
+        if byte == b'=' {
+            break;
+        }
+        let value = BASE64_ALPHABET.iter().position(|c| *c == byte)? as u32;
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    if output.is_empty() {
+        None
+    } else {
+        Some(output)
+    }
+}
+
+fn base64_encode_unpadded(input: &[u8]) -> String {
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let b0 = chunk[0] as u32;
+        let b1 = chunk.get(1).copied().unwrap_or(0) as u32;
+        let b2 = chunk.get(2).copied().unwrap_or(0) as u32;
+        let triple = (b0 << 16) | (b1 << 8) | b2;
+        let symbols = chunk.len() + 1;
+        for i in 0..symbols {
+            let index = (triple >> (18 - 6 * i)) & 0x3f;
+            output.push(BASE64_ALPHABET[index as usize] as char);
+        }
+    }
+    output
+}
+
+fn looks_temporary(path: &str) -> bool {
+    path.starts_with("/tmp/") || path.starts_with("/var/tmp/") || path.starts_with("/dev/shm/")
+}
@@ -1,36 +1,209 @@
 use crate::ScanOutcome;
 use std::{
+    collections::{HashMap, HashSet},
     fs,
+    os::unix::fs::MetadataExt,
     path::{Path, PathBuf},
 };
 
+const MAX_INCLUDE_DEPTH: usize = 8;
+const MAX_ALIAS_DEPTH: usize = 16;
+
+const ALIAS_KINDS: &[&str] = &[
+    "User_Alias",
+    "Runas_Alias",
+    "Host_Alias",
+    "Cmnd_Alias",
+    "Cmd_Alias",
+];
+
+const TAGS: &[&str] = &[
+    "NOPASSWD",
+    "PASSWD",
+    "NOEXEC",
+    "EXEC",
+    "SETENV",
+    "NOSETENV",
+    "LOG_INPUT",
+    "NOLOG_INPUT",
+    "LOG_OUTPUT",
+    "NOLOG_OUTPUT",
+    "MAIL",
+    "NOMAIL",
+    "FOLLOW",
+    "NOFOLLOW",
+    "INTERCEPT",
+    "NOINTERCEPT",
+];
+
+const DANGEROUS_ENV: &[&str] = &[
+    "LD_PRELOAD",
+    "LD_LIBRARY_PATH",
+    "LD_AUDIT",
+    "PYTHONPATH",
+    "PYTHONSTARTUP",
+    "PERL5LIB",
+    "PERL5OPT",
+    "RUBYLIB",
+    "NODE_OPTIONS",
+    "BASH_ENV",
+    "ENV",
+    "PATH",
+];
+
+// Binaries with well-known shell escapes when run through sudo (GTFOBins).
+const SHELL_ESCAPES: &[&str] = &[
+    "sh",
+    "bash",
+    "dash",
+    "zsh",
+    "ksh",
+    "csh",
+    "tcsh",
+    "fish",
+    "busybox",
+    "env",
+    "find",
+    "vi",
+    "vim",
+    "view",
+    "nano",
+    "pico",
+    "ed",
+    "less",
+    "more",
+    "man",
+    "awk",
+    "gawk",
+    "mawk",
+    "nawk",
+    "perl",
+    "python",
+    "python2",
+    "python3",
+    "ruby",
+    "lua",
+    "php",
+    "node",
+    "tclsh",
+    "expect",
+    "ftp",
+    "gdb",
+    "git",
+    "tar",
+    "zip",
+    "rsync",
+    "scp",
+    "ssh",
+    "nmap",
+    "tee",
+    "cp",
+    "mv",
+    "dd",
+    "chmod",
+    "chown",
+    "journalctl",
+    "systemctl",
+    "docker",
+    "crontab",
+    "apt",
+    "apt-get",
+    "yum",
+    "dnf",
+    "pip",
+    "pip3",
+    "make",
+    "sed",
+    "nice",
+    "taskset",
+    "timeout",
+    "strace",
+    "ltrace",
+    "xargs",
+    "watch",
+    "script",
+    "socat",
+    "nc",
+    "ncat",
+    "openssl",
+    "wget",
+    "curl",
+    "su",
+    "sudo",
+    "tcpdump",
+    "mount",
+    "flock",
+    "ionice",
+    "stdbuf",
+    "unshare",
+    "nsenter",
+    "setarch",
+];
+
+struct LogicalLine {
+    file: PathBuf,
+    line_no: usize,
+    text: String,
+}
+
+#[derive(Default)]
+struct Aliases {
+    by_kind: HashMap<String, HashMap<String, Vec<String>>>,
+}
+
+#[derive(Clone, Default)]
+struct CommandContext {
+    runas: String,
+    nopasswd: bool,
+    setenv: bool,
+}
+
 pub fn run() -> ScanOutcome {
     let mut findings = Vec::new();
     let mut errors = Vec::new();
 
-    let paths = vec![PathBuf::from("/etc/sudoers")];
-    for path in paths {
-        match analyze_file(&path) {
-            Ok(mut list) => findings.append(&mut list),
-            Err(err) => errors.push(err),
-        }
-    }
+    let mut lines = Vec::new();
+    let mut visited = HashSet::new();
+    collect_lines(
+        Path::new("/etc/sudoers"),
+        0,
+        &mut visited,
+        &mut lines,
+        &mut findings,
+        &mut errors,
+    );
 
+    // Distros that ship /etc/sudoers.d without an includedir still leave files there.
     let dir = Path::new("/etc/sudoers.d");
     if dir.exists() {
-        match fs::read_dir(dir) {
-            Ok(entries) => {
-                for entry in entries.flatten() {
-                    if entry.path().is_file() {
-                        match analyze_file(&entry.path()) {
-                            Ok(mut list) => findings.append(&mut list),
-                            Err(err) => errors.push(err),
-                        }
-                    }
-                }
-            }
-            Err(err) => errors.push(format!("failed to read {}: {err}", dir.display())),
+        for path in includedir_files(dir, &mut errors) {
+            collect_lines(
+                &path,
+                0,
+                &mut visited,
+                &mut lines,
+                &mut findings,
+                &mut errors,
+            );
+        }
+    }
+
+    let mut aliases = Aliases::default();
+    for line in &lines {
+        parse_alias(&line.text, &mut aliases);
+    }
+
+    for line in &lines {
+        let text = line.text.as_str();
+        let keyword = text.split_whitespace().next().unwrap_or("");
+        if ALIAS_KINDS.contains(&keyword) {
+            continue;
+        }
+        if keyword.starts_with("Defaults") {
+            findings.extend(analyze_defaults(line));
+            continue;
         }
+        findings.extend(analyze_user_spec(line, &aliases));
     }
 
     if findings.is_empty() {
@@ -41,6 +214,7 @@ pub fn run() -> ScanOutcome {
         }
     } else {
         findings.sort();
+        findings.dedup();
         if !errors.is_empty() {
             findings.push(format!("collection_errors={}", errors.join(", ")));
         }
@@ -48,33 +222,491 @@ pub fn run() -> ScanOutcome {
     }
 }
 
-fn analyze_file(path: &Path) -> Result<Vec<String>, String> {
-    let content = fs::read_to_string(path)
-        .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
-    let mut findings = Vec::new();
+fn collect_lines(
+    path: &Path,
+    depth: usize,
+    visited: &mut HashSet<PathBuf>,
+    lines: &mut Vec<LogicalLine>,
+    findings: &mut Vec<String>,
+    errors: &mut Vec<String>,
+) {
+    if depth > MAX_INCLUDE_DEPTH {
+        errors.push(format!("include depth exceeded at {}", path.display()));
+        return;
+    }
+    if !visited.insert(path.to_path_buf()) {
+        return;
+    }
 
-    for (idx, line) in content.lines().enumerate() {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with('#') {
-            continue;
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) => {
+            errors.push(format!("failed to read {}: {err}", path.display()));
+            return;
         }
-        if trimmed.contains("NOPASSWD: ALL") {
+    };
+
+    match fs::metadata(path) {
+        Ok(meta) if meta.uid() != 0 || meta.mode() & 0o022 != 0 => {
             findings.push(format!(
-                "file={}, line_no={}, entry='{}'",
+                "file={}, owner_uid={}, mode={:o}, issue=file_insecure",
                 path.display(),
-                idx + 1,
-                trimmed
+                meta.uid(),
+                meta.mode() & 0o7777
             ));
         }
-        if trimmed.contains("ALL=(ALL) ALL") && trimmed.contains("!authenticate") {
+        _ => {}
+    }
+
+    let base = path.parent().unwrap_or(Path::new("/etc"));
+    let mut pending = String::new();
+    let mut start_line = 0usize;
+
+    for (idx, raw) in content.lines().enumerate() {
+        if pending.is_empty() {
+            start_line = idx + 1;
+        }
+        if let Some(stripped) = raw.strip_suffix('\\') {
+            pending.push_str(stripped);
+            pending.push(' ');
+            continue;
+        }
+        pending.push_str(raw);
+        let text = pending.trim().to_string();
+        pending.clear();
+
+        if text.is_empty() {
+            continue;
+        }
+
+        if let Some((directive, target)) = include_directive(&text) {
+            let target = if target.starts_with('/') {
+                PathBuf::from(target)
+            } else {
+                base.join(target)
+            };
+            if directive.ends_with("includedir") {
+                for path in includedir_files(&target, errors) {
+                    collect_lines(&path, depth + 1, visited, lines, findings, errors);
+                }
+            } else {
+                collect_lines(&target, depth + 1, visited, lines, findings, errors);
+            }
+            continue;
+        }
+
+        let text = strip_comment(&text).trim_end();
+        if text.is_empty() {
+            continue;
+        }
+
+        lines.push(LogicalLine {
+            file: path.to_path_buf(),
+            line_no: start_line,
+            text: text.to_string(),
+        });
+    }
+}
+
+fn strip_comment(text: &str) -> &str {
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for (idx, ch) in text.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match ch {
+            '\\' => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            // `#1000` is a uid, not a comment.
+            '#' if !in_quotes && !text[idx + 1..].starts_with(|c: char| c.is_ascii_digit()) => {
+                return &text[..idx];
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+fn include_directive(text: &str) -> Option<(&str, &str)> {
+    let mut parts = text.splitn(2, char::is_whitespace);
+    let directive = parts.next()?;
+    match directive {
+        "#include" | "@include" | "#includedir" | "@includedir" => {
+            let target = parts.next()?.trim().trim_matches('"');
+            Some((directive, target))
+        }
+        _ => None,
+    }
+}
+
+fn includedir_files(dir: &Path, errors: &mut Vec<String>) -> Vec<PathBuf> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            errors.push(format!("failed to read {}: {err}", dir.display()));
+            return Vec::new();
+        }
+    };
+
+    // sudo skips names ending in `~` or containing a dot.
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|name| !name.ends_with('~') && !name.contains('.'))
+                    .unwrap_or(false)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn parse_alias(text: &str, aliases: &mut Aliases) {
+    let Some((kind, rest)) = text.split_once(char::is_whitespace) else {
+        return;
+    };
+    if !ALIAS_KINDS.contains(&kind) {
+        return;
+    }
+    let kind = if kind == "Cmd_Alias" {
+        "Cmnd_Alias"
+    } else {
+        kind
+    };
+    let table = aliases.by_kind.entry(kind.to_string()).or_default();
+
+    for definition in split_top_level(rest, ':') {
+        let Some((name, members)) = definition.split_once('=') else {
+            continue;
+        };
+        let members = split_top_level(members, ',')
+            .into_iter()
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty())
+            .collect();
+        table.insert(name.trim().to_string(), members);
+    }
+}
+
+fn expand(aliases: &Aliases, kind: &str, items: &[String], depth: usize) -> Vec<String> {
+    let table = aliases.by_kind.get(kind);
+    let mut expanded = Vec::new();
+    for item in items {
+        let (negated, name) = match item.strip_prefix('!') {
+            Some(rest) => (true, rest.trim()),
+            None => (false, item.as_str()),
+        };
+        match table.and_then(|t| t.get(name)) {
+            Some(members) if depth < MAX_ALIAS_DEPTH => {
+                if negated {
+                    continue;
+                }
+                expanded.extend(expand(aliases, kind, members, depth + 1));
+            }
+            _ => {
+                if !negated {
+                    expanded.push(name.to_string());
+                }
+            }
+        }
+    }
+    expanded
+}
+
+fn split_top_level(text: &str, separator: char) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0i32;
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    for ch in text.chars() {
+        if escaped {
+            current.push(ch);
+            escaped = false;
+            continue;
+        }
+        match ch {
+            '\\' => {
+                current.push(ch);
+                escaped = true;
+            }
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(ch);
+            }
+            '(' if !in_quotes => {
+                depth += 1;
+                current.push(ch);
+            }
+            ')' if !in_quotes => {
+                depth -= 1;
+                current.push(ch);
+            }
+            c if c == separator && depth == 0 && !in_quotes => {
+                parts.push(std::mem::take(&mut current));
+            }
+            _ => current.push(ch),
+        }
+    }
+    parts.push(current);
+    parts
+}
+
+fn analyze_defaults(line: &LogicalLine) -> Vec<String> {
+    let mut findings = Vec::new();
+    let (head, params) = line
+        .text
+        .split_once(char::is_whitespace)
+        .unwrap_or((line.text.as_str(), ""));
+
+    for param in split_top_level(params, ',') {
+        let param = param.trim();
+        if param.is_empty() {
+            continue;
+        }
+        let mut issues = Vec::new();
+        let (name, value) = match param.split_once('=') {
+            Some((name, value)) => (
+                name.trim().trim_end_matches(['+', '-']).trim(),
+                Some(value.trim().trim_matches('"')),
+            ),
+            None => (param, None),
+        };
+
+        match (name, value) {
+            ("!env_reset", None) => issues.push("env_reset_disabled"),
+            ("!requiretty", None) => issues.push("requiretty_disabled"),
+            ("!authenticate", None) => issues.push("authentication_disabled"),
+            ("setenv", None) => issues.push("setenv_allowed"),
+            ("env_keep" | "env_check", Some(value))
+                if !param.contains("-=")
+                    && value
+                        .split_whitespace()
+                        .any(|var| DANGEROUS_ENV.contains(&var.trim_end_matches('*'))) =>
+            {
+                issues.push("env_keep_dangerous")
+            }
+            ("secure_path", Some(value)) if value.split(':').any(directory_untrusted) => {
+                issues.push("secure_path_writable")
+            }
+            _ => {}
+        }
+
+        for issue in issues {
             findings.push(format!(
-                "file={}, line_no={}, entry='{}'",
-                path.display(),
-                idx + 1,
-                trimmed
+                "file={}, line_no={}, defaults='{} {}', issue={}",
+                line.file.display(),
+                line.line_no,
+                head,
+                param,
+                issue
             ));
         }
     }
 
-    Ok(findings)
+    findings
+}
+
+fn analyze_user_spec(line: &LogicalLine, aliases: &Aliases) -> Vec<String> {
+    let mut findings = Vec::new();
+    let Some(eq) = line.text.find('=') else {
+        return findings;
+    };
+    let head = line.text[..eq].replace(", ", ",");
+    let mut head_parts = head.split_whitespace();
+    let Some(users) = head_parts.next() else {
+        return findings;
+    };
+    let users: Vec<String> = users.split(',').map(str::to_string).collect();
+    let users = expand(aliases, "User_Alias", &users, 0);
+
+    // The first host list is part of `head`; later ones precede `=` inside each segment.
+    let mut segments = Vec::new();
+    for (idx, segment) in split_host_segments(&line.text[eq + 1..])
+        .into_iter()
+        .enumerate()
+    {
+        if idx == 0 {
+            segments.push(segment);
+        } else if let Some((_, cmnds)) = segment.split_once('=') {
+            segments.push(cmnds.to_string());
+        }
+    }
+
+    for segment in segments {
+        let mut context = CommandContext {
+            runas: "root".to_string(),
+            ..Default::default()
+        };
+        for spec in split_top_level(&segment, ',') {
+            let command = parse_command_spec(spec.trim(), &mut context, aliases);
+            let commands = expand(aliases, "Cmnd_Alias", &[command], 0);
+            for command in commands {
+                for issue in evaluate_command(&command, &context) {
+                    findings.push(format!(
+                        "file={}, line_no={}, users={}, runas={}, command={}, issue={}",
+                        line.file.display(),
+                        line.line_no,
+                        users.join("|"),
+                        context.runas,
+                        command,
+                        issue
+                    ));
+                }
+            }
+        }
+    }
+
+    findings
+}
+
+fn split_host_segments(text: &str) -> Vec<String> {
+    // A `:` separates host specs unless it terminates a tag or sits inside a runas spec.
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0i32;
+    let mut escaped = false;
+
+    for ch in text.chars() {
+        if escaped {
+            current.push(ch);
+            escaped = false;
+            continue;
+        }
+        match ch {
+            '\\' => {
+                current.push(ch);
+                escaped = true;
+            }
+            '(' => {
+                depth += 1;
+                current.push(ch);
+            }
+            ')' => {
+                depth -= 1;
+                current.push(ch);
+            }
+            ':' if depth == 0 => {
+                let word = current
+                    .rsplit(|c: char| c.is_whitespace() || matches!(c, ',' | ')' | ':'))
+                    .next()
+                    .unwrap_or("");
+                if TAGS.contains(&word) {
+                    current.push(ch);
+                } else {
+                    segments.push(std::mem::take(&mut current));
+                }
+            }
+            _ => current.push(ch),
+        }
+    }
+    segments.push(current);
+    segments
+}
+
+fn parse_command_spec(spec: &str, context: &mut CommandContext, aliases: &Aliases) -> String {
+    let mut rest = spec.trim();
+
+    if let Some((runas, after)) = rest.strip_prefix('(').and_then(|r| r.split_once(')')) {
+        let runas = runas.trim();
+        let user_part = runas.split(':').next().unwrap_or("").trim();
+        let list: Vec<String> = if user_part.is_empty() {
+            vec!["root".to_string()]
+        } else {
+            user_part.split(',').map(|u| u.trim().to_string()).collect()
+        };
+        context.runas = expand(aliases, "Runas_Alias", &list, 0).join("|");
+        rest = after.trim_start();
+    }
+
+    while let Some((word, after)) = rest.split_once(':') {
+        let word = word.trim();
+        if !TAGS.contains(&word) {
+            break;
+        }
+        match word {
+            "NOPASSWD" => context.nopasswd = true,
+            "PASSWD" => context.nopasswd = false,
+            "SETENV" => context.setenv = true,
+            "NOSETENV" => context.setenv = false,
+            _ => {}
+        }
+        rest = after.trim_start();
+    }
+
+    rest.to_string()
+}
+
+fn evaluate_command(command: &str, context: &CommandContext) -> Vec<String> {
+    let mut issues = Vec::new();
+    let command = command.trim();
+    if command.is_empty() {
+        return issues;
+    }
+
+    let program = command.split_whitespace().next().unwrap_or("");
+    let binary = program.rsplit('/').next().unwrap_or(program);
+
+    if context.setenv {
+        issues.push("setenv_tag".to_string());
+    }
+
+    if !context.nopasswd {
+        return issues;
+    }
+
+    if command == "ALL" {
+        issues.push("nopasswd_all".to_string());
+        return issues;
+    }
+
+    if SHELL_ESCAPES.contains(&binary) {
+        issues.push(format!("nopasswd_shell_escape({binary})"));
+    }
+    if command[program.len()..].contains(['*', '?']) {
+        issues.push("nopasswd_wildcard_args".to_string());
+    }
+    if let Some(issue) = program_untrusted(program) {
+        issues.push(issue.to_string());
+    }
+
+    issues
+}
+
+fn program_untrusted(program: &str) -> Option<&'static str> {
+    if !program.starts_with('/') {
+        return None;
+    }
+    match fs::metadata(program) {
+        Ok(meta) if meta.uid() != 0 || meta.mode() & 0o022 != 0 => Some("command_writable"),
+        Ok(_) => {
+            let parent = Path::new(program).parent()?;
+            if directory_untrusted(parent.to_str()?) {
+                Some("command_dir_writable")
+            } else {
+                None
+            }
+        }
+        Err(_) => Some("command_missing"),
+    }
+}
+
+fn directory_untrusted(dir: &str) -> bool {
+    if ["/tmp", "/var/tmp", "/dev/shm"].iter().any(|root| {
+        dir.strip_prefix(root)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    }) {
+        return true;
+    }
+    match fs::metadata(dir) {
+        Ok(meta) => meta.uid() != 0 || meta.mode() & 0o022 != 0,
+        Err(_) => false,
+    }
 }
@@ -56,15 +56,15 @@ pub fn run() -> ScanOutcome {
     }
 }
 
-struct TaskInfo {
-    pid: i32,
-    comm: String,
-    uid: u32,
-    tracer_pid: Option<i32>,
-    ppid: i32,
+pub struct TaskInfo {
+    pub pid: i32,
+    pub comm: String,
+    pub uid: u32,
+    pub tracer_pid: Option<i32>,
+    pub ppid: i32,
 }
 
-fn collect_tasks() -> Result<HashMap<i32, TaskInfo>, String> {
+pub fn collect_tasks() -> Result<HashMap<i32, TaskInfo>, String> {
     let mut map = HashMap::new();
     let proc_dir = fs::read_dir("/proc").map_err(|err| format!("failed to read /proc: {err}"))?;
 
@@ -0,0 +1,565 @@
+use super::ftrace_redirection::{find_tracefs_root, is_sensitive_symbol};
+use crate::ScanOutcome;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs,
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+const LIVEPATCH_ROOT: &str = "/sys/kernel/livepatch";
+const TMP_PREFIXES: &[&str] = &["/tmp/", "/var/tmp/", "/dev/shm/", "/run/shm/"];
+// Largest distance from a kallsyms entry that still counts as inside it.
+const MAX_SYMBOL_SPAN: u64 = 0x10000;
+
+// Options that silence trace_printk output, trace_marker writes or the
+// function tracer itself.
+const SUPPRESSING_OPTIONS: &[&str] = &["notrace_printk", "nomarkers", "nofunction-trace"];
+
+// Binaries and libraries whose uprobes can sniff credentials or keystrokes.
+const CREDENTIAL_TARGETS: &[&str] = &[
+    "libpam",
+    "pam_unix",
+    "libssl",
+    "libcrypto",
+    "libgnutls",
+    "libnss3",
+    "libreadline",
+    "sshd",
+    "ssh",
+    "ssh-agent",
+    "sudo",
+    "su",
+    "passwd",
+    "login",
+    "bash",
+    "gpg-agent",
+];
+
+type Kallsyms = BTreeMap<u64, (String, Option<String>)>;
+
+struct Callback {
+    text: String,
+    module: Option<String>,
+    direct: bool,
+}
+
+struct FtraceRecord {
+    function: String,
+    flags: Vec<String>,
+    callbacks: Vec<Callback>,
+}
+
+pub fn run() -> ScanOutcome {
+    let root = find_tracefs_root().map_err(|err| format!("failed to locate tracefs: {err}"))?;
+
+    let mut findings = Vec::new();
+    let mut errors = Vec::new();
+
+    // Kernels built without module support have no /proc/modules at all.
+    let modules = match loaded_modules() {
+        Ok(modules) => modules,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => HashSet::new(),
+        Err(err) => return Err(format!("failed to read /proc/modules: {err}")),
+    };
+    // Without readable kallsyms raw callback addresses cannot be attributed.
+    let kallsyms = match load_kallsyms() {
+        Ok(map) if !map.is_empty() => Some(map),
+        Ok(_) => {
+            errors.push("kallsyms empty or restricted, raw callbacks left unresolved".to_string());
+            None
+        }
+        Err(err) => {
+            errors.push(format!("failed to read /proc/kallsyms: {err}"));
+            None
+        }
+    };
+
+    let livepatched = inspect_livepatch(&modules, &mut findings, &mut errors);
+
+    let enabled = match read_optional(&root.join("enabled_functions")) {
+        Ok(content) => parse_ftrace_records(content.as_deref().unwrap_or("")),
+        Err(err) => {
+            errors.push(format!("enabled_functions: {err}"));
+            Vec::new()
+        }
+    };
+    let enabled_names: HashSet<&str> = enabled.iter().map(|r| r.function.as_str()).collect();
+    for record in &enabled {
+        let issues = assess_record(record, &modules, kallsyms.as_ref(), &livepatched);
+        if issues.is_empty() {
+            continue;
+        }
+        let callbacks: Vec<String> = record.callbacks.iter().map(describe_callback).collect();
+        findings.push(format!(
+            "source=enabled_functions, function={}, flags={}, callbacks={}, issues={}",
+            record.function,
+            if record.flags.is_empty() {
+                "-".to_string()
+            } else {
+                record.flags.join("|")
+            },
+            if callbacks.is_empty() {
+                "-".to_string()
+            } else {
+                callbacks.join("|")
+            },
+            issues.join(",")
+        ));
+    }
+
+    // touched_functions remembers every record ever attached since boot, so a
+    // sensitive function that is no longer hooked still leaves a trace here.
+    match read_optional(&root.join("touched_functions")) {
+        Ok(Some(content)) => {
+            let previously: BTreeSet<String> = parse_ftrace_records(&content)
+                .into_iter()
+                .filter(|record| !enabled_names.contains(record.function.as_str()))
+                .filter(|record| sensitive(&record.function))
+                .map(|record| record.function)
+                .collect();
+            if !previously.is_empty() {
+                findings.push(format!(
+                    "source=touched_functions, sensitive_previously_hooked={}",
+                    previously.into_iter().collect::<Vec<_>>().join("|")
+                ));
+            }
+        }
+        Ok(None) => {}
+        Err(err) => errors.push(format!("touched_functions: {err}")),
+    }
+
+    match read_optional(&root.join("uprobe_events")) {
+        Ok(Some(content)) => findings.extend(inspect_uprobes(&content)),
+        Ok(None) => {}
+        Err(err) => errors.push(format!("uprobe_events: {err}")),
+    }
+
+    match read_optional(&root.join("dynamic_events")) {
+        Ok(Some(content)) => findings.extend(inspect_dynamic_events(&content)),
+        Ok(None) => {}
+        Err(err) => errors.push(format!("dynamic_events: {err}")),
+    }
+
+    match read_optional(&root.join("set_graph_function")) {
+        Ok(Some(content)) => {
+            let graphed: BTreeSet<&str> = content
+                .lines()
+                .filter_map(|line| line.split_whitespace().next())
+                .filter(|symbol| !symbol.starts_with('#') && sensitive(symbol))
+                .collect();
+            if !graphed.is_empty() {
+                findings.push(format!(
+                    "source=set_graph_function, sensitive={}",
+                    graphed.into_iter().collect::<Vec<_>>().join("|")
+                ));
+            }
+        }
+        Ok(None) => {}
+        Err(err) => errors.push(format!("set_graph_function: {err}")),
+    }
+
+    match read_optional(&root.join("trace_options")) {
+        Ok(Some(content)) => {
+            let suppressed: Vec<&str> = content
+                .lines()
+                .map(str::trim)
+                .filter(|option| SUPPRESSING_OPTIONS.contains(option))
+                .collect();
+            if !suppressed.is_empty() {
+                findings.push(format!(
+                    "source=trace_options, suppressed={}",
+                    suppressed.join("|")
+                ));
+            }
+        }
+        Ok(None) => {}
+        Err(err) => errors.push(format!("trace_options: {err}")),
+    }
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+/// Parses enabled_functions/touched_functions records such as
+/// `vfs_read (1) R I   \ttramp: 0xffffffffc0a01000 (hook+0x0/0x60 [evil]) ->hook+0x0/0x60 [evil]`
+/// followed by optional `\tdirect-->target` continuation lines.
+fn parse_ftrace_records(content: &str) -> Vec<FtraceRecord> {
+    let mut records: Vec<FtraceRecord> = Vec::new();
+
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with(char::is_whitespace) {
+            if let Some(record) = records.last_mut() {
+                record.callbacks.extend(parse_callbacks(line));
+            }
+            continue;
+        }
+
+        // The function may carry a " [module]" suffix, so anchor on "(count)".
+        let Some((function, rest)) = split_at_count(line) else {
+            continue;
+        };
+        // Flags come before the first tab; a bare " ->func" may follow them.
+        let (head, annotations) = rest.split_once('\t').unwrap_or((rest, ""));
+        let (flags, others): (Vec<&str>, Vec<&str>) = head
+            .split_whitespace()
+            .partition(|token| matches!(*token, "R" | "I" | "D" | "O" | "M"));
+        let mut callbacks = parse_callbacks(&others.join(" "));
+        callbacks.extend(parse_callbacks(annotations));
+
+        records.push(FtraceRecord {
+            function: function.trim().to_string(),
+            flags: flags.into_iter().map(str::to_string).collect(),
+            callbacks,
+        });
+    }
+
+    records
+}
+
+fn split_at_count(line: &str) -> Option<(&str, &str)> {
+    let mut search = line;
+    let mut offset = 0;
+    while let Some(idx) = search.find(" (") {
+        let start = offset + idx;
+        let after = &line[start + 2..];
+        match after.find(')') {
+            Some(end) if end > 0 && after[..end].bytes().all(|b| b.is_ascii_digit()) => {
+                return Some((&line[..start], &after[end + 1..]));
+            }
+            _ => {}
+        }
+        offset = start + 2;
+        search = &line[offset..];
+    }
+    None
+}
+
+/// Extracts every `%pS` rendered address from the trampoline, ops and direct
+/// call annotations, pairing each with its `[module]` suffix when present.
+fn parse_callbacks(text: &str) -> Vec<Callback> {
+    let mut callbacks: Vec<Callback> = Vec::new();
+    for raw in text.split_whitespace() {
+        if matches!(raw, "tramp:" | "ops:") {
+            continue;
+        }
+        let token = raw.trim_start_matches('(').trim_end_matches(')');
+        if let Some(module) = token
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            if let Some(last) = callbacks.last_mut() {
+                last.module = Some(module.to_string());
+            }
+            continue;
+        }
+        let (token, direct) = match token.strip_prefix("direct-->") {
+            Some(target) => (target, true),
+            None => (token.strip_prefix("->").unwrap_or(token), false),
+        };
+        if token.is_empty() {
+            continue;
+        }
+        let duplicate = callbacks.iter().any(|existing| existing.text == token);
+        if !duplicate {
+            callbacks.push(Callback {
+                text: token.to_string(),
+                module: None,
+                direct,
+            });
+        }
+    }
+    callbacks
+}
+
+fn assess_record(
+    record: &FtraceRecord,
+    modules: &HashSet<String>,
+    kallsyms: Option<&Kallsyms>,
+    livepatched: &HashSet<String>,
+) -> Vec<String> {
+    let mut issues = Vec::new();
+    let base_name = record.function.split_whitespace().next().unwrap_or("");
+    let mut module_owned = Vec::new();
+
+    for callback in &record.callbacks {
+        if callback.text.contains("ERROR!") {
+            issues.push("tramp_error".to_string());
+            continue;
+        }
+        let raw = callback.text.strip_prefix("0x");
+        let (symbol, module) = match (&callback.module, raw, kallsyms) {
+            (Some(module), _, _) => (callback.text.clone(), Some(module.clone())),
+            (None, Some(hex), Some(kallsyms)) => match u64::from_str_radix(hex, 16)
+                .ok()
+                .and_then(|addr| resolve(kallsyms, addr))
+            {
+                Some((name, module)) => (name, module),
+                None => {
+                    issues.push(format!("unresolved_callback({})", callback.text));
+                    continue;
+                }
+            },
+            (None, _, _) => (callback.text.clone(), None),
+        };
+        let Some(module) = module else {
+            continue;
+        };
+        if is_kernel_managed(&module) {
+            continue;
+        }
+        if !modules.contains(&module) {
+            issues.push(format!("hidden_module_owner({module}:{symbol})"));
+        } else {
+            module_owned.push(module);
+        }
+    }
+
+    if record.flags.iter().any(|flag| flag == "I") && !livepatched.contains(base_name) {
+        issues.push("ipmodify_without_livepatch".to_string());
+    }
+    // Direct calls are normally BPF trampolines; anything else is a custom
+    // ftrace_ops redirecting the function wholesale.
+    for callback in record.callbacks.iter().filter(|callback| callback.direct) {
+        let bpf = callback.text.starts_with("bpf_trampoline_")
+            || callback.module.as_deref() == Some("bpf");
+        if !bpf {
+            issues.push(format!(
+                "direct_call_non_bpf({})",
+                describe_callback(callback)
+            ));
+        }
+    }
+    if sensitive(base_name) && !module_owned.is_empty() && !livepatched.contains(base_name) {
+        module_owned.sort();
+        module_owned.dedup();
+        issues.push(format!("sensitive_module_hook({})", module_owned.join("|")));
+    }
+
+    issues
+}
+
+fn describe_callback(callback: &Callback) -> String {
+    match &callback.module {
+        Some(module) => format!("{}[{}]", callback.text, module),
+        None => callback.text.clone(),
+    }
+}
+
+/// Pseudo-modules kallsyms uses for BPF programs and ftrace/kprobe trampolines.
+fn is_kernel_managed(module: &str) -> bool {
+    module == "bpf" || module.starts_with("__builtin__")
+}
+
+fn sensitive(symbol: &str) -> bool {
+    let symbol = ["__x64_", "__ia32_", "__arm64_", "__se_", "__do_"]
+        .iter()
+        .find_map(|prefix| symbol.strip_prefix(prefix))
+        .unwrap_or(symbol);
+    is_sensitive_symbol(symbol)
+}
+
+fn inspect_uprobes(content: &str) -> Vec<String> {
+    let mut findings = Vec::new();
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(event), Some(target)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let location = target.split('(').next().unwrap_or(target);
+        let Some((path, offset)) = location.rsplit_once(':') else {
+            continue;
+        };
+
+        let mut issues = Vec::new();
+        if TMP_PREFIXES.iter().any(|prefix| path.starts_with(prefix)) {
+            issues.push("target_tmp".to_string());
+        }
+        if fs::metadata(path).is_err() {
+            issues.push("target_missing".to_string());
+        }
+        let base = path.rsplit('/').next().unwrap_or(path);
+        let credential = CREDENTIAL_TARGETS.iter().any(|name| {
+            base == *name
+                || base
+                    .strip_prefix(name)
+                    .is_some_and(|rest| rest.starts_with(['.', '-']))
+        });
+        if credential {
+            issues.push("credential_target".to_string());
+        }
+        if issues.is_empty() {
+            continue;
+        }
+
+        findings.push(format!(
+            "source=uprobe_events, event={}, target={}, offset={}, issues={}",
+            event,
+            path,
+            offset,
+            issues.join(",")
+        ));
+    }
+    findings
+}
+
+/// Covers the probe kinds only visible through dynamic_events; kprobes and
+/// uprobes are handled by their dedicated files.
+fn inspect_dynamic_events(content: &str) -> Vec<String> {
+    let mut findings = Vec::new();
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(event), Some(target)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let kind = match event.chars().next() {
+            Some('f') => "fprobe",
+            Some('t') => "tprobe",
+            Some('e') => "eprobe",
+            _ => continue,
+        };
+        let symbol = target.split(['%', '+', ':', '.']).next().unwrap_or(target);
+        if !sensitive(symbol) {
+            continue;
+        }
+        findings.push(format!(
+            "source=dynamic_events, type={}, target={}, raw={}",
+            kind,
+            symbol,
+            line.trim()
+        ));
+    }
+    findings
+}
+
+/// Reports livepatches backed by unlisted modules or touching sensitive
+/// functions, and returns the set of patched function names.
+fn inspect_livepatch(
+    modules: &HashSet<String>,
+    findings: &mut Vec<String>,
+    errors: &mut Vec<String>,
+) -> HashSet<String> {
+    let mut patched = HashSet::new();
+    let entries = match fs::read_dir(LIVEPATCH_ROOT) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return patched,
+        Err(err) => {
+            errors.push(format!("{LIVEPATCH_ROOT}: {err}"));
+            return patched;
+        }
+    };
+
+    for patch in entries.flatten() {
+        let name = patch.file_name().to_string_lossy().into_owned();
+        let enabled = fs::read_to_string(patch.path().join("enabled"))
+            .map(|value| value.trim() == "1")
+            .unwrap_or(false);
+
+        let mut functions = BTreeSet::new();
+        for object in fs::read_dir(patch.path()).into_iter().flatten().flatten() {
+            if !object.path().is_dir() {
+                continue;
+            }
+            for function in fs::read_dir(object.path()).into_iter().flatten().flatten() {
+                if !function.path().is_dir() {
+                    continue;
+                }
+                let entry = function.file_name().to_string_lossy().into_owned();
+                let symbol = entry.split(',').next().unwrap_or(&entry).to_string();
+                functions.insert(symbol);
+            }
+        }
+        if enabled {
+            patched.extend(functions.iter().cloned());
+        }
+
+        let mut issues = Vec::new();
+        if !modules.contains(&name) {
+            issues.push("module_not_in_proc_modules".to_string());
+        }
+        let touched: Vec<&str> = functions
+            .iter()
+            .map(String::as_str)
+            .filter(|symbol| sensitive(symbol))
+            .collect();
+        if !touched.is_empty() {
+            issues.push(format!("patches_sensitive({})", touched.join("|")));
+        }
+        if issues.is_empty() {
+            continue;
+        }
+        findings.push(format!(
+            "source=livepatch, patch={}, enabled={}, functions={}, issues={}",
+            name,
+            enabled,
+            functions.len(),
+            issues.join(",")
+        ));
+    }
+
+    patched
+}
+
+fn resolve(kallsyms: &Kallsyms, addr: u64) -> Option<(String, Option<String>)> {
+    let (base, (name, module)) = kallsyms.range(..=addr).next_back()?;
+    if addr - base > MAX_SYMBOL_SPAN {
+        return None;
+    }
+    Some((format!("{name}+{:#x}", addr - base), module.clone()))
+}
+
+fn load_kallsyms() -> io::Result<Kallsyms> {
+    let reader = BufReader::new(fs::File::open("/proc/kallsyms")?);
+    let mut map = BTreeMap::new();
+    for line in reader.lines() {
+        let line = line?;
+        let mut parts = line.split_whitespace();
+        let (Some(addr), Some(_), Some(name)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+        let Ok(addr) = u64::from_str_radix(addr, 16) else {
+            continue;
+        };
+        // Restricted kallsyms reports every address as zero.
+        if addr == 0 {
+            return Ok(BTreeMap::new());
+        }
+        let module = parts
+            .next()
+            .and_then(|m| m.strip_prefix('[')?.strip_suffix(']'))
+            .map(str::to_string);
+        map.insert(addr, (name.to_string(), module));
+    }
+    Ok(map)
+}
+
+fn loaded_modules() -> io::Result<HashSet<String>> {
+    let content = fs::read_to_string("/proc/modules")?;
+    Ok(content
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_string)
+        .collect())
+}
+
+fn read_optional(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
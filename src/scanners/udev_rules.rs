@@ -0,0 +1,372 @@
+use super::modprobe_hotplug::investigate_target;
+use crate::ScanOutcome;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+// Highest precedence first: a file in /etc replaces the same name in /run,
+// which replaces the vendor copy.
+const ADMIN_RULE_DIRS: &[&str] = &["/etc/udev/rules.d", "/run/udev/rules.d"];
+const VENDOR_RULE_DIRS: &[&str] = &["/usr/lib/udev/rules.d", "/lib/udev/rules.d"];
+const UDEV_LIB_DIRS: &[&str] = &["/usr/lib/udev", "/lib/udev"];
+const COMMON_SUBSYSTEMS: &[&str] = &["net", "block", "usb", "tty", "input", "pci", "module"];
+const DOWNLOADERS: &[&str] = &["curl", "wget", "nc", "ncat", "netcat", "socat"];
+const SHELLS: &[&str] = &["sh", "bash", "dash", "zsh", "ksh", "busybox"];
+
+struct Assignment {
+    key: String,
+    attr: Option<String>,
+    op: String,
+    value: String,
+}
+
+struct Rule {
+    line: usize,
+    // Inside a GOTO ... LABEL block, which carries its own match keys.
+    guarded: bool,
+    assignments: Vec<Assignment>,
+}
+
+impl Rule {
+    // Builtins (RUN{builtin}, IMPORT{builtin}) never execute a file.
+    fn commands(&self) -> Vec<&str> {
+        self.assignments
+            .iter()
+            .filter(|assignment| {
+                matches!(
+                    (assignment.key.as_str(), assignment.attr.as_deref()),
+                    ("RUN", None | Some("program")) | ("PROGRAM", _) | ("IMPORT", Some("program"))
+                )
+            })
+            .map(|assignment| assignment.value.as_str())
+            .collect()
+    }
+
+    fn broad_trigger(&self) -> Option<&'static str> {
+        if self.guarded {
+            return None;
+        }
+        let matches: Vec<&Assignment> = self
+            .assignments
+            .iter()
+            .filter(|assignment| matches!(assignment.op.as_str(), "==" | "!="))
+            .filter(|assignment| assignment.key != "ACTION")
+            .collect();
+        match matches.as_slice() {
+            [] => Some("fires_on_every_event"),
+            [only]
+                if only.key == "SUBSYSTEM"
+                    && only.op == "=="
+                    && COMMON_SUBSYSTEMS.contains(&only.value.as_str()) =>
+            {
+                Some("fires_on_common_event")
+            }
+            _ => None,
+        }
+    }
+}
+
+pub fn run() -> ScanOutcome {
+    let mut findings = Vec::new();
+    let mut errors = Vec::new();
+
+    let admin = collect_rule_files(ADMIN_RULE_DIRS, &mut errors);
+    let vendor = collect_rule_files(VENDOR_RULE_DIRS, &mut errors);
+
+    for (name, path) in &admin {
+        // A /dev/null symlink masks the vendor rule of the same name.
+        if fs::read_link(path).is_ok_and(|target| target == Path::new("/dev/null")) {
+            continue;
+        }
+
+        let rules = match parse_rules_file(path) {
+            Ok(rules) => rules,
+            Err(err) => {
+                errors.push(err);
+                continue;
+            }
+        };
+        let vendor_commands: Option<BTreeSet<String>> = vendor.get(name).map(|vendor_path| {
+            parse_rules_file(vendor_path)
+                .unwrap_or_default()
+                .iter()
+                .flat_map(|rule| rule.commands())
+                .map(str::to_string)
+                .collect()
+        });
+
+        for rule in &rules {
+            for command in rule.commands() {
+                let mut anomalies = Vec::new();
+                if let Some(anomaly) = evaluate_exec(command, true) {
+                    anomalies.push(anomaly);
+                }
+                if let Some(trigger) = rule.broad_trigger() {
+                    anomalies.push(trigger);
+                }
+                if vendor_commands
+                    .as_ref()
+                    .is_some_and(|known| !known.contains(command))
+                {
+                    anomalies.push("shadows_vendor_rule");
+                }
+                if !anomalies.is_empty() {
+                    findings.push(format!(
+                        "rule={}:{}, exec={}, anomaly={}",
+                        path.display(),
+                        rule.line,
+                        command,
+                        anomalies.join("|")
+                    ));
+                }
+            }
+        }
+    }
+
+    // Shadowed vendor files are never loaded.
+    for (name, path) in &vendor {
+        if admin.contains_key(name) {
+            continue;
+        }
+        let rules = match parse_rules_file(path) {
+            Ok(rules) => rules,
+            Err(err) => {
+                errors.push(err);
+                continue;
+            }
+        };
+        for rule in &rules {
+            for command in rule.commands() {
+                if let Some(anomaly) = evaluate_exec(command, false) {
+                    findings.push(format!(
+                        "rule={}:{}, exec={}, anomaly={}",
+                        path.display(),
+                        rule.line,
+                        command,
+                        anomaly
+                    ));
+                }
+            }
+        }
+    }
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+fn collect_rule_files(dirs: &[&str], errors: &mut Vec<String>) -> BTreeMap<String, PathBuf> {
+    let mut files = BTreeMap::new();
+    let mut seen_dirs = Vec::new();
+    for dir in dirs {
+        let Ok(resolved) = fs::canonicalize(dir) else {
+            continue;
+        };
+        if seen_dirs.contains(&resolved) {
+            continue;
+        }
+        seen_dirs.push(resolved);
+
+        match fs::read_dir(dir) {
+            Ok(entries) => {
+                for entry in entries.flatten() {
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    if name.ends_with(".rules") {
+                        files.entry(name).or_insert_with(|| entry.path());
+                    }
+                }
+            }
+            Err(err) => errors.push(format!("failed to read {dir}: {err}")),
+        }
+    }
+    files
+}
+
+fn parse_rules_file(path: &Path) -> Result<Vec<Rule>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+
+    let mut rules = Vec::new();
+    let mut pending = String::new();
+    let mut start = 0;
+    let mut open_gotos: Vec<String> = Vec::new();
+    for (idx, raw) in content.lines().enumerate() {
+        if pending.is_empty() {
+            start = idx + 1;
+        }
+        if let Some(head) = raw.strip_suffix('\\') {
+            pending.push_str(head);
+            continue;
+        }
+        pending.push_str(raw);
+        let line = std::mem::take(&mut pending);
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let assignments = parse_assignments(line);
+        for assignment in &assignments {
+            if assignment.key == "LABEL" {
+                open_gotos.retain(|label| *label != assignment.value);
+            }
+        }
+        let guarded = !open_gotos.is_empty();
+        for assignment in &assignments {
+            if assignment.key == "GOTO" {
+                open_gotos.push(assignment.value.clone());
+            }
+        }
+        rules.push(Rule {
+            line: start,
+            guarded,
+            assignments,
+        });
+    }
+    Ok(rules)
+}
+
+fn parse_assignments(line: &str) -> Vec<Assignment> {
+    let mut assignments = Vec::new();
+    let mut rest = line;
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        if rest.is_empty() {
+            break;
+        }
+
+        let key_end = rest
+            .find(['{', '=', '!', '+', '-', ':'])
+            .unwrap_or(rest.len());
+        let key = rest[..key_end].trim().to_string();
+        rest = &rest[key_end..];
+
+        let mut attr = None;
+        if let Some(inner) = rest.strip_prefix('{') {
+            let Some((name, tail)) = inner.split_once('}') else {
+                break;
+            };
+            attr = Some(name.to_string());
+            rest = tail;
+        }
+
+        let Some(op) = ["==", "!=", "+=", "-=", ":=", "="]
+            .into_iter()
+            .find(|op| rest.starts_with(op))
+        else {
+            break;
+        };
+        rest = &rest[op.len()..];
+
+        let Some(quoted) = rest.strip_prefix('"') else {
+            break;
+        };
+        let mut value = String::new();
+        let mut end = quoted.len();
+        let mut escaped = false;
+        for (idx, c) in quoted.char_indices() {
+            match c {
+                '"' if !escaped => {
+                    end = idx;
+                    break;
+                }
+                '\\' if !escaped => escaped = true,
+                c => {
+                    if escaped && c != '"' {
+                        value.push('\\');
+                    }
+                    value.push(c);
+                    escaped = false;
+                }
+            }
+        }
+        rest = quoted.get(end + 1..).unwrap_or("");
+
+        assignments.push(Assignment {
+            key,
+            attr,
+            op: op.to_string(),
+            value,
+        });
+    }
+
+    assignments
+}
+
+// Vendor rules routinely reference tools that are not installed, so only
+// admin rules report missing programs.
+fn evaluate_exec(command: &str, admin: bool) -> Option<&'static str> {
+    let token = command.split_whitespace().next()?;
+    let token = token.trim_matches(['"', '\'']);
+
+    if downloads(command) {
+        return Some("exec_downloads");
+    }
+    if command.contains("/tmp/") || command.contains("/var/tmp/") || command.contains("/dev/shm/") {
+        return Some("exec_in_tmp");
+    }
+    if command.contains("/home/") || command.contains("/root/") {
+        return Some("exec_in_home");
+    }
+
+    let program = if token.starts_with('/') {
+        Some(PathBuf::from(token))
+    } else {
+        UDEV_LIB_DIRS
+            .iter()
+            .map(|dir| Path::new(dir).join(token))
+            .find(|candidate| candidate.exists())
+    };
+    let Some(program) = program else {
+        return (admin && !token.contains('$')).then_some("exec_missing");
+    };
+    let program = program.to_string_lossy();
+    match investigate_target(&program) {
+        Ok(issues) if issues.contains(&"missing_target") => {
+            if program.contains("(deleted)") {
+                Some("exec_deleted")
+            } else {
+                admin.then_some("exec_missing")
+            }
+        }
+        Ok(issues) if !issues.is_empty() => Some("exec_writable"),
+        _ => None,
+    }
+}
+
+fn downloads(command: &str) -> bool {
+    if command.contains("/dev/tcp/") || command.contains("/dev/udp/") {
+        return true;
+    }
+    let mut words = command
+        .split_whitespace()
+        .map(|word| word.trim_matches(['"', '\'']));
+    let Some(program) = words.next().map(basename) else {
+        return false;
+    };
+    if DOWNLOADERS.contains(&program) {
+        return true;
+    }
+    if !SHELLS.contains(&program) || !words.any(|word| word == "-c") {
+        return false;
+    }
+    words
+        .flat_map(|word| word.split([';', '|', '&', '(', ')', '`', '"', '\'']))
+        .any(|word| DOWNLOADERS.contains(&basename(word)))
+}
+
+fn basename(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
@@ -0,0 +1,325 @@
+use super::ownerless_sockets::collect_owners;
+use crate::ScanOutcome;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    os::unix::fs::MetadataExt,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+const SO_ACCEPTCON: u32 = 0x0001_0000;
+const TMP_PREFIXES: &[&str] = &["/tmp/", "/var/tmp/", "/dev/shm/", "/run/shm/"];
+
+// Well-known world-writable socket directories created by X11 and friends.
+const STANDARD_SOCKET_DIRS: &[&str] = &[
+    "/tmp/.X11-unix",
+    "/tmp/.ICE-unix",
+    "/tmp/.XIM-unix",
+    "/tmp/.font-unix",
+    "/tmp/.Test-unix",
+];
+
+struct UnixSocket {
+    kind: &'static str,
+    listening: bool,
+    path: Option<String>,
+}
+
+pub fn run() -> ScanOutcome {
+    if Command::new("ss")
+        .arg("-V")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_err()
+    {
+        return Err("ss not available to query sockets".to_string());
+    }
+
+    let mut errors = Vec::new();
+
+    // Read /proc on both sides of the ss call so sockets created or closed in
+    // between are not mistaken for hidden ones.
+    let before = collect_proc()?;
+    let diag = match collect_ss() {
+        Ok(map) => map,
+        Err(err) => {
+            errors.push(err);
+            BTreeMap::new()
+        }
+    };
+    let after = collect_proc()?;
+
+    let mut owners: BTreeMap<String, BTreeSet<i32>> = BTreeMap::new();
+    collect_owners("/proc", &mut owners)
+        .map_err(|err| format!("failed to enumerate fd owners: {err}"))?;
+
+    let mut findings = Vec::new();
+
+    if errors.is_empty() {
+        for (inode, (socket, pids)) in &diag {
+            if before.contains_key(inode) || after.contains_key(inode) {
+                continue;
+            }
+            findings.push(format!(
+                "from=sockdiag_only, inode={}, type={}, path={}, owner_pids={}",
+                inode,
+                socket.kind,
+                socket.path.as_deref().unwrap_or("-"),
+                format_pids(pids)
+            ));
+        }
+        for (inode, socket) in &before {
+            if diag.contains_key(inode) || !after.contains_key(inode) {
+                continue;
+            }
+            findings.push(format!(
+                "from=proc_only, inode={}, type={}, path={}, owner_pids={}",
+                inode,
+                socket.kind,
+                socket.path.as_deref().unwrap_or("-"),
+                owners
+                    .get(inode)
+                    .map(format_pids)
+                    .unwrap_or_else(|| "∅".to_string())
+            ));
+        }
+    }
+
+    let mut exe_cache: BTreeMap<i32, String> = BTreeMap::new();
+    let mut reported_paths = BTreeSet::new();
+
+    for (inode, socket) in &after {
+        let pids = owners.get(inode).cloned().unwrap_or_default();
+        let mut issues = Vec::new();
+
+        if socket.listening && pids.is_empty() {
+            issues.push("listening_no_owner".to_string());
+        }
+
+        match socket.path.as_deref() {
+            Some(name) if name.starts_with('@') => {
+                let volatile: Vec<String> = pids
+                    .iter()
+                    .filter_map(|pid| {
+                        let exe = exe_cache.entry(*pid).or_insert_with(|| read_exe(*pid));
+                        volatile_origin(exe).map(|origin| format!("{pid}:{origin}"))
+                    })
+                    .collect();
+                if !volatile.is_empty() {
+                    issues.push(format!("abstract_volatile_owner({})", volatile.join("|")));
+                }
+            }
+            // Accepted connections repeat the listener's path; only judge the
+            // bound endpoint itself.
+            Some(path)
+                if (socket.listening || socket.kind == "dgram")
+                    && reported_paths.insert(path.to_string()) =>
+            {
+                let root = pids
+                    .first()
+                    .map(|pid| format!("/proc/{pid}/root"))
+                    .unwrap_or_default();
+                if let Some(dir) = world_writable_parent(&root, path) {
+                    issues.push(format!("world_writable_dir({dir})"));
+                }
+            }
+            _ => {}
+        }
+
+        if issues.is_empty() {
+            continue;
+        }
+
+        let exes: BTreeSet<String> = pids
+            .iter()
+            .map(|pid| {
+                exe_cache
+                    .entry(*pid)
+                    .or_insert_with(|| read_exe(*pid))
+                    .clone()
+            })
+            .collect();
+        findings.push(format!(
+            "inode={}, type={}, state={}, path={}, owner_pids={}, owner_exes={}, issues={}",
+            inode,
+            socket.kind,
+            if socket.listening { "listen" } else { "bound" },
+            socket.path.as_deref().unwrap_or("-"),
+            format_pids(&pids),
+            if exes.is_empty() {
+                "∅".to_string()
+            } else {
+                exes.into_iter().collect::<Vec<_>>().join("|")
+            },
+            issues.join(",")
+        ));
+    }
+
+    if findings.is_empty() {
+        if errors.is_empty() {
+            Ok(None)
+        } else {
+            Err(errors.join(", "))
+        }
+    } else {
+        findings.sort();
+        if !errors.is_empty() {
+            findings.push(format!("collection_errors={}", errors.join(", ")));
+        }
+        Ok(Some(findings.join("\n")))
+    }
+}
+
+fn collect_proc() -> Result<BTreeMap<String, UnixSocket>, String> {
+    let content = fs::read_to_string("/proc/net/unix")
+        .map_err(|err| format!("failed to read /proc/net/unix: {err}"))?;
+    let mut map = BTreeMap::new();
+
+    // Num RefCount Protocol Flags Type St Inode Path
+    for line in content.lines().skip(1) {
+        // Paths may contain spaces, so peel off the fixed columns one by one
+        // and keep the remainder verbatim.
+        let mut fields = Vec::new();
+        let mut rest = line;
+        for _ in 0..7 {
+            let trimmed = rest.trim_start();
+            let (field, tail) = trimmed.split_once(' ').unwrap_or((trimmed, ""));
+            fields.push(field);
+            rest = tail;
+        }
+        if fields[6].is_empty() || fields[6] == "0" {
+            continue;
+        }
+        let path = rest.trim();
+        let flags = u32::from_str_radix(fields[3], 16).unwrap_or(0);
+        map.insert(
+            fields[6].to_string(),
+            UnixSocket {
+                kind: kind_from_proc(fields[4]),
+                listening: flags & SO_ACCEPTCON != 0,
+                path: (!path.is_empty()).then(|| path.to_string()),
+            },
+        );
+    }
+
+    Ok(map)
+}
+
+fn collect_ss() -> Result<BTreeMap<String, (UnixSocket, BTreeSet<i32>)>, String> {
+    let output = Command::new("ss")
+        .args(["-H", "-a", "-n", "-p", "-x"])
+        .output()
+        .map_err(|err| format!("failed to execute ss -x: {err}"))?;
+
+    if !output.status.success() {
+        return Err(format!("ss -x exited with {}", output.status));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut map = BTreeMap::new();
+
+    // Netid State Recv-Q Send-Q Local Inode Peer PeerInode [users:(...)]
+    for line in stdout.lines() {
+        let head = line.split(" users:(").next().unwrap_or(line);
+        let tokens: Vec<&str> = head.split_whitespace().collect();
+        if tokens.len() < 8 {
+            continue;
+        }
+        let inode = tokens[tokens.len() - 3];
+        if inode.parse::<u64>().is_err() || inode == "0" {
+            continue;
+        }
+        let local = tokens[4..tokens.len() - 3].join(" ");
+        map.insert(
+            inode.to_string(),
+            (
+                UnixSocket {
+                    kind: kind_from_ss(tokens[0]),
+                    listening: tokens[1] == "LISTEN",
+                    path: (local != "*").then_some(local),
+                },
+                extract_pids(line),
+            ),
+        );
+    }
+
+    Ok(map)
+}
+
+fn kind_from_proc(raw: &str) -> &'static str {
+    match raw {
+        "0001" => "stream",
+        "0002" => "dgram",
+        "0005" => "seqpacket",
+        _ => "unknown",
+    }
+}
+
+fn kind_from_ss(raw: &str) -> &'static str {
+    match raw {
+        "u_str" => "stream",
+        "u_dgr" => "dgram",
+        "u_seq" => "seqpacket",
+        _ => "unknown",
+    }
+}
+
+fn extract_pids(line: &str) -> BTreeSet<i32> {
+    let mut set = BTreeSet::new();
+    let mut remaining = line;
+    while let Some(idx) = remaining.find("pid=") {
+        let tail = &remaining[idx + 4..];
+        let pid_str: String = tail.chars().take_while(|c| c.is_ascii_digit()).collect();
+        if let Ok(pid) = pid_str.parse::<i32>() {
+            set.insert(pid);
+        }
+        remaining = &tail[pid_str.len()..];
+    }
+    set
+}
+
+fn format_pids(pids: &BTreeSet<i32>) -> String {
+    if pids.is_empty() {
+        return "∅".to_string();
+    }
+    pids.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+fn read_exe(pid: i32) -> String {
+    fs::read_link(format!("/proc/{pid}/exe"))
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+fn volatile_origin(exe: &str) -> Option<&'static str> {
+    if exe.contains("memfd:") {
+        Some("memfd")
+    } else if exe.ends_with(" (deleted)") {
+        Some("deleted")
+    } else if TMP_PREFIXES.iter().any(|prefix| exe.starts_with(prefix)) {
+        Some("tmp")
+    } else {
+        None
+    }
+}
+
+/// Returns the socket's directory when any user may write to it, resolved
+/// inside the owner's root so container sockets are judged in place. A
+/// private directory such as `/tmp/tmux-1000` protects the socket no matter
+/// what lies above it.
+fn world_writable_parent(root: &str, path: &str) -> Option<String> {
+    let dir = Path::new(path).parent()?;
+    let shown = dir.to_string_lossy();
+    if STANDARD_SOCKET_DIRS.contains(&shown.as_ref()) {
+        return None;
+    }
+    match fs::metadata(format!("{root}{shown}")) {
+        Ok(meta) if meta.is_dir() && meta.mode() & 0o002 != 0 => Some(shown.into_owned()),
+        _ => None,
+    }
+}